@@ -2,6 +2,7 @@ use clap::{Parser, ValueEnum};
 use std::io::{self, BufRead};
 use std::path::PathBuf;
 
+use btmeister::defs::Category;
 use btmeister::{IgnoreType, LogLevel, MeisterError, Result};
 
 #[derive(Parser, Debug)]
@@ -68,17 +69,157 @@ pub(crate) struct InputOpts {
         short,
         long,
         value_name = "EXCLUDEs",
-        help = "Specify the filters of excluding files or directories."
+        help = "Specify the gitignore style patterns of excluding files or directories relative to the project.
+The pattern starting with \"re:\" is a regular expression."
     )]
     pub(crate) excludes: Vec<String>,
 
+    #[arg(
+        short = 't',
+        long,
+        default_value_t = 1,
+        value_name = "NUM",
+        help = "Specify the number of threads for traversing directories (0: automatic)."
+    )]
+    pub(crate) threads: usize,
+
+    #[arg(
+        long,
+        value_name = "NAMEs",
+        value_delimiter = ',',
+        help = "Detect only the build tools of the given names in the definitions."
+    )]
+    pub(crate) only: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "NAMEs",
+        value_delimiter = ',',
+        help = "Ignore the build tools of the given names in the definitions."
+    )]
+    pub(crate) exclude_tool: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Report all of the matched build tools for each file, not only the first one."
+    )]
+    pub(crate) multi_match: bool,
+
+    #[arg(
+        long,
+        help = "List the tasks defined in the build files of the task runners (Make, just, Task, and npm)."
+    )]
+    pub(crate) tasks: bool,
+
+    #[arg(
+        long,
+        help = "Detect the pinned versions and the wrapper scripts of the build tools (e.g., the Gradle wrapper)."
+    )]
+    pub(crate) versions: bool,
+
+    #[arg(
+        long,
+        help = "Count the files of each language in the projects by the file extensions (not shown in csv format)."
+    )]
+    pub(crate) languages: bool,
+
+    #[arg(
+        long,
+        value_name = "REV",
+        help = "Scan the given revision of the git repositories in PROJECTs without checking it out."
+    )]
+    pub(crate) rev: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "CACHE_FILE",
+        help = "Cache the entries of the directories in the given file, and read only the modified directories on the next run."
+    )]
+    pub(crate) cache: Option<PathBuf>,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        value_name = "DEPTH",
+        help = "Descend into the archives nested in the archive files up to the given depth (e.g., jar files in tar.gz).
+The entries in the nested archives are shown like \"lib/app.jar!/pom.xml\"."
+    )]
+    pub(crate) archive_depth: usize,
+
+    #[arg(
+        long,
+        help = "Detect the build tools also in the archive files found in the directories of PROJECTs."
+    )]
+    pub(crate) open_archives: bool,
+
+    #[arg(
+        long,
+        help = "Report each project root in the archive files of PROJECTs as its own project (e.g., \"dist.zip!/app\")."
+    )]
+    pub(crate) archive_roots: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "rev",
+        help = "Print the timeline of the build tools across the commits of the git repositories in PROJECTs."
+    )]
+    pub(crate) history: bool,
+
+    #[arg(
+        long,
+        requires = "history",
+        help = "Scan only the tagged commits in the history mode."
+    )]
+    pub(crate) tags_only: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["history", "rev"],
+        help = "Print the added, removed, and moved build files between two PROJECTs.
+The saved results of \"--format json\" are also accepted as PROJECTs."
+    )]
+    pub(crate) diff: bool,
+
+    #[arg(
+        long,
+        value_name = "POLICY_JSON",
+        conflicts_with_all = ["history", "diff"],
+        help = "Check the build tools of PROJECTs against the rules in the given policy file,
+and exit with non-zero status if any rule fails."
+    )]
+    pub(crate) check: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "RESULT_JSON",
+        conflicts_with_all = ["history", "diff", "check"],
+        help = "Compare the build tools of PROJECTs with the saved result of \"--format json\",
+print the unexpected and missing build files, and exit with non-zero status if they differ."
+    )]
+    pub(crate) expect: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "expect",
+        help = "Rewrite the file given by \"--expect\" with the current result."
+    )]
+    pub(crate) update_expect: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["history", "diff", "check", "expect", "rev"],
+        help = "Watch the directories in PROJECTs, and print the added and removed build files as JSON lines
+until interrupted (the output format is always JSON lines)."
+    )]
+    pub(crate) watch: bool,
+
     #[arg(
         value_name = "PROJECTs",
         required = false,
         help = "The target project paths. If \"-\" was given, reads from stdin.
 Also, the first character was \"@\", read from the file eliminating \"@\".
-This parameters accept directories and archive files.
-Supported archive files: tar, tar.bz2, tar.gz, tar.xz, tar.zstd, and zip."
+This parameters accept directories, archive files, and git revisions (REPOSITORY@REV).
+Supported archive files: tar, tar.bz2, tar.gz, tar.xz, tar.zstd, zip, ar, and deb."
     )]
     pub dirs: Vec<String>,
 }
@@ -92,6 +233,12 @@ pub(crate) struct OutputOpts {
     )]
     pub(crate) list_defs: bool,
 
+    #[arg(
+        long,
+        help = "Print only the files of the primary build tool for each project."
+    )]
+    pub(crate) primary: bool,
+
     #[arg(
         short,
         long,
@@ -102,6 +249,12 @@ pub(crate) struct OutputOpts {
         help = "Specify the output format"
     )]
     pub(crate) format: Format,
+
+    #[arg(
+        long,
+        help = "Print the header row and the version, wrapper, alternatives, confidence, and categories columns in the CSV format."
+    )]
+    pub(crate) csv_extended: bool,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -120,6 +273,31 @@ pub(crate) struct DefOpts {
         help = "Specify the additional definitions of the build tools."
     )]
     pub(crate) append_defs: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "LANGS_JSON",
+        help = "Specify the definition of the languages for --languages."
+    )]
+    pub(crate) language_defs: Option<PathBuf>,
+
+    #[arg(
+        long = "category",
+        value_name = "CATEGORY",
+        value_enum,
+        ignore_case = true,
+        help = "Use only the build tools of the given categories."
+    )]
+    pub(crate) categories: Vec<Category>,
+
+    #[arg(
+        long = "exclude-category",
+        value_name = "CATEGORY",
+        value_enum,
+        ignore_case = true,
+        help = "Ignore the build tools of the given categories."
+    )]
+    pub(crate) exclude_categories: Vec<Category>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
@@ -158,10 +336,29 @@ fn read_from_file(filename: &str) -> Result<Vec<String>> {
     }
 }
 
-fn convert_and_push_item(item: &str, result: &mut Vec<PathBuf>, errs: &mut Vec<MeisterError>) {
+fn convert_and_push_item(
+    item: &str,
+    rev: &Option<String>,
+    result: &mut Vec<PathBuf>,
+    errs: &mut Vec<MeisterError>,
+) {
     let path = PathBuf::from(item);
-    if !path.exists() {
-        errs.push(MeisterError::ProjectNotFound(path));
+    if let Some(rev) = rev {
+        let arg = format!("{}@{}", item, rev);
+        if btmeister::parse_revision(&arg) == Some((path, rev.clone())) {
+            result.push(PathBuf::from(arg));
+        } else {
+            errs.push(MeisterError::NotProject(format!(
+                "{} (not a git repository)",
+                item
+            )));
+        }
+    } else if !path.exists() {
+        if btmeister::parse_revision(&path).is_some() {
+            result.push(path);
+        } else {
+            errs.push(MeisterError::ProjectNotFound(path));
+        }
     } else if path.is_file() {
         if btmeister::is_supported_archive_format(&path) {
             result.push(path);
@@ -177,6 +374,7 @@ fn convert_and_push_item(item: &str, result: &mut Vec<PathBuf>, errs: &mut Vec<M
 
 fn push_items_or_errs(
     r: Result<Vec<String>>,
+    rev: &Option<String>,
     results: &mut Vec<PathBuf>,
     errs: &mut Vec<MeisterError>,
 ) {
@@ -184,7 +382,7 @@ fn push_items_or_errs(
         Err(e) => errs.push(e),
         Ok(items) => {
             for item in items {
-                convert_and_push_item(&item, results, errs)
+                convert_and_push_item(&item, rev, results, errs)
             }
         }
     }
@@ -196,11 +394,11 @@ impl InputOpts {
         let mut result = vec![];
         for item in self.dirs.iter() {
             if item == "-" {
-                push_items_or_errs(read_from_stdin(), &mut result, &mut errs);
+                push_items_or_errs(read_from_stdin(), &self.rev, &mut result, &mut errs);
             } else if let Some(stripped) = item.strip_prefix('@') {
-                push_items_or_errs(read_from_file(stripped), &mut result, &mut errs);
+                push_items_or_errs(read_from_file(stripped), &self.rev, &mut result, &mut errs);
             } else {
-                convert_and_push_item(item.as_str(), &mut result, &mut errs);
+                convert_and_push_item(item.as_str(), &self.rev, &mut result, &mut errs);
             }
         }
         if !errs.is_empty() {
@@ -284,11 +482,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rev_for_non_repository() {
+        let opts = Options::parse_from(["meister", "--rev", "v1", "../testdata/hello"]);
+        let projects = opts.inputs.projects();
+        match projects {
+            Err(MeisterError::Array(e)) => {
+                assert_eq!(1, e.len());
+                assert!(matches!(&e[0], MeisterError::NotProject(_)));
+            }
+            _ => panic!("fatal: {:?}", projects),
+        }
+    }
+
     #[test]
     fn test_no_projects() {
         let opts = InputOpts {
             ignore_types: vec![],
             excludes: vec![],
+            threads: 1,
+            only: vec![],
+            exclude_tool: vec![],
+            multi_match: false,
+            tasks: false,
+            versions: false,
+            languages: false,
+            rev: None,
+            cache: None,
+            archive_depth: 0,
+            open_archives: false,
+            archive_roots: false,
+            history: false,
+            tags_only: false,
+            diff: false,
+            check: None,
+            expect: None,
+            update_expect: false,
+            watch: false,
             dirs: vec![],
         };
         let projects = opts.projects();
@@ -13,8 +13,13 @@ use crate::fmt::json::Formatter as JsonFormatter;
 use crate::fmt::markdown::Formatter as MarkdownFormatter;
 use crate::fmt::xml::Formatter as XmlFormatter;
 use crate::fmt::yaml::Formatter as YamlFormatter;
+use btmeister::diff::{Change, Diff};
+use btmeister::history::{Commit, Timeline};
+use btmeister::policy::Report;
 use btmeister::{BuildTools, Result};
 
+pub(crate) use json::event_line;
+
 pub trait Formatter {
     #[cfg(test)]
     fn name(&self) -> &'static str;
@@ -27,11 +32,31 @@ pub trait Formatter {
     fn header_files(&self) -> Option<String>;
     fn footer_files(&self) -> Option<String>;
     fn format_files(&self, tools: &BuildTools, first: bool) -> Result<String>;
+
+    fn header_history(&self) -> Option<String> {
+        self.header_files()
+    }
+    fn footer_history(&self) -> Option<String> {
+        self.footer_files()
+    }
+    fn format_history(&self, timeline: &Timeline, first: bool) -> Result<String>;
+
+    fn format_diff(&self, diff: &Diff) -> Result<String>;
+
+    fn header_check(&self) -> Option<String> {
+        self.header_files()
+    }
+    fn footer_check(&self) -> Option<String> {
+        self.footer_files()
+    }
+    fn format_check(&self, report: &Report, first: bool) -> Result<String>;
 }
 
-pub fn build_formatter(format: Format) -> Box<dyn Formatter> {
+/// build_formatter returns the formatter of the given format.
+/// `extended` prints the header row and the additional columns in the CSV format, and is ignored by the other formats.
+pub fn build_formatter(format: Format, extended: bool) -> Box<dyn Formatter> {
     match format {
-        Format::Csv => Box::new(CsvFormatter {}),
+        Format::Csv => Box::new(CsvFormatter { extended }),
         Format::Default => Box::new(DefaultFormatter {}),
         Format::Json => Box::new(JsonFormatter {}),
         Format::Markdown => Box::new(MarkdownFormatter {}),
@@ -51,36 +76,235 @@ pub fn fake_build_def() -> BuildToolDef {
 
 #[cfg(test)]
 pub fn fake_build_tools() -> btmeister::BuildTools {
-    btmeister::BuildTools {
-        base: std::path::PathBuf::from("fake/base/dir"),
-        tools: vec![
-            btmeister::BuildTool {
-                path: "fake/base/dir/Fakefile".into(),
-                def: fake_build_def(),
-            },
-            btmeister::BuildTool {
-                path: "fake/base/dir/Makefile".into(),
-                def: BuildToolDef::new(
+    btmeister::BuildTools::new(
+        std::path::PathBuf::from("fake/base/dir"),
+        vec![
+            btmeister::BuildTool::new("fake/base/dir/Fakefile".into(), fake_build_def()),
+            btmeister::BuildTool::new(
+                "fake/base/dir/Makefile".into(),
+                BuildToolDef::new(
                     "Make".to_string(),
                     vec!["Makefile".to_string()],
                     "https://example.com".to_string(),
                 ),
+            ),
+        ],
+    )
+}
+
+#[cfg(test)]
+pub fn fake_language_build_tools() -> btmeister::BuildTools {
+    let mut tools = fake_build_tools();
+    tools.languages = vec![
+        btmeister::langs::LanguageCount {
+            name: "Rust".to_string(),
+            files: 12,
+        },
+        btmeister::langs::LanguageCount {
+            name: "Shell".to_string(),
+            files: 1,
+        },
+    ];
+    tools
+}
+
+#[cfg(test)]
+pub fn fake_pinned_build_tools() -> btmeister::BuildTools {
+    let mut bt = btmeister::BuildTool::new(
+        "fake/base/dir/build.gradle".into(),
+        BuildToolDef::new(
+            "Gradle".to_string(),
+            vec!["build.gradle".to_string()],
+            "https://example.com".to_string(),
+        ),
+    );
+    bt.version = Some("8.5".to_string());
+    bt.wrapper = true;
+    btmeister::BuildTools::new(std::path::PathBuf::from("fake/base/dir"), vec![bt])
+}
+
+#[cfg(test)]
+pub fn fake_task_build_tools() -> btmeister::BuildTools {
+    let mut bt = btmeister::BuildTool::new(
+        "fake/base/dir/Makefile".into(),
+        BuildToolDef::new(
+            "Make".to_string(),
+            vec!["Makefile".to_string()],
+            "https://example.com".to_string(),
+        ),
+    );
+    bt.tasks = vec!["build".to_string(), "test".to_string()];
+    btmeister::BuildTools::new(std::path::PathBuf::from("fake/base/dir"), vec![bt])
+}
+
+#[cfg(test)]
+pub fn fake_multi_match_build_tools() -> btmeister::BuildTools {
+    let mut bt = btmeister::BuildTool::new("fake/base/dir/BUILD".into(), fake_build_def());
+    bt.alternatives = vec![
+        BuildToolDef::new(
+            "Bazel".to_string(),
+            vec!["BUILD".to_string()],
+            "https://example.com".to_string(),
+        ),
+        BuildToolDef::new(
+            "Pants".to_string(),
+            vec!["BUILD".to_string()],
+            "https://example.com".to_string(),
+        ),
+    ];
+    btmeister::BuildTools::new(std::path::PathBuf::from("fake/base/dir"), vec![bt])
+}
+
+#[cfg(test)]
+pub fn fake_categorized_build_tools() -> btmeister::BuildTools {
+    use btmeister::defs::Category;
+    btmeister::BuildTools::new(
+        std::path::PathBuf::from("fake/base/dir"),
+        vec![btmeister::BuildTool::new(
+            "fake/base/dir/Makefile".into(),
+            BuildToolDef::new(
+                "Make".to_string(),
+                vec!["Makefile".to_string()],
+                "https://example.com".to_string(),
+            )
+            .with_categories(vec![Category::TaskRunner, Category::Build]),
+        )],
+    )
+}
+
+#[cfg(test)]
+pub fn fake_timeline() -> Timeline {
+    let commit = |id: &str, tag: &str, date: &str| Commit {
+        id: id.to_string(),
+        tag: Some(tag.to_string()),
+        date: date.to_string(),
+    };
+    let v1 = commit("1111111111", "v1", "2020-01-01T00:00:00+09:00");
+    let v2 = commit("2222222222", "v2", "2021-01-01T00:00:00+09:00");
+    Timeline {
+        repository: std::path::PathBuf::from("fake/repo.git"),
+        commits: vec![v1.clone(), v2.clone()],
+        spans: vec![
+            btmeister::history::Span {
+                name: "Make".to_string(),
+                appeared: v1,
+                disappeared: Some(v2.clone()),
+            },
+            btmeister::history::Span {
+                name: "Gradle".to_string(),
+                appeared: v2,
+                disappeared: None,
             },
         ],
     }
 }
 
+#[cfg(test)]
+pub fn fake_diff() -> Diff {
+    Diff {
+        before: std::path::PathBuf::from("v1"),
+        after: std::path::PathBuf::from("v2"),
+        added_tools: vec!["Gradle".to_string()],
+        removed_tools: vec!["Apache Maven".to_string()],
+        changes: vec![
+            Change::Moved {
+                name: "Make".to_string(),
+                from: "Makefile".to_string(),
+                to: "tools/Makefile".to_string(),
+            },
+            Change::Added {
+                name: "Gradle".to_string(),
+                path: "build.gradle".to_string(),
+            },
+            Change::Removed {
+                name: "Apache Maven".to_string(),
+                path: "pom.xml".to_string(),
+            },
+        ],
+    }
+}
+
+#[cfg(test)]
+pub fn fake_report() -> Report {
+    use btmeister::policy::{Rule, Violation};
+    Report {
+        base: std::path::PathBuf::from("fake/base/dir"),
+        violations: vec![
+            Violation {
+                rule: Rule::Deny,
+                path: Some(".travis.yml".to_string()),
+                message: "Travis is denied".to_string(),
+            },
+            Violation {
+                rule: Rule::RequireOneOf,
+                path: None,
+                message: "none of GitHub Actions, GitLab CI/CD is used".to_string(),
+            },
+        ],
+    }
+}
+
+/// change_kind returns the kind of the given change (`added`, `removed`, or `moved`).
+pub(crate) fn change_kind(change: &Change) -> &'static str {
+    match change {
+        Change::Added { .. } => "added",
+        Change::Removed { .. } => "removed",
+        Change::Moved { .. } => "moved",
+    }
+}
+
+/// commit_label returns the label of the given commit with its date for the human readable formats,
+/// e.g., `v1.2.0 (2020-01-01T00:00:00+09:00)`.
+pub(crate) fn commit_label(commit: &Commit) -> String {
+    format!("{} ({})", commit.label(), commit.date)
+}
+
+/// category_names returns the names of the categories of the given definition.
+pub(crate) fn category_names(def: &BuildToolDef) -> Vec<String> {
+    def.categories.iter().map(|c| c.to_string()).collect()
+}
+
+/// alternative_names returns the names of the alternative definitions of the given build tool.
+pub(crate) fn alternative_names(bt: &btmeister::BuildTool) -> Vec<String> {
+    bt.alternatives.iter().map(|d| d.name.clone()).collect()
+}
+
+/// tool_label returns the name of the given build tool with its annotations for the human readable formats,
+/// e.g., `Gradle [build] (version 7.4.2, wrapper)` and `Bazel (also: Pants)`.
+pub(crate) fn tool_label(bt: &btmeister::BuildTool) -> String {
+    let mut label = bt.def.name.clone();
+    let categories = category_names(&bt.def);
+    if !categories.is_empty() {
+        label.push_str(&format!(" [{}]", categories.join(", ")));
+    }
+    let mut details = vec![];
+    if let Some(version) = &bt.version {
+        details.push(format!("version {}", version));
+    }
+    if bt.wrapper {
+        details.push("wrapper".to_string());
+    }
+    if !details.is_empty() {
+        label.push_str(&format!(" ({})", details.join(", ")));
+    }
+    let alternatives = alternative_names(bt);
+    if !alternatives.is_empty() {
+        label.push_str(&format!(" (also: {})", alternatives.join(", ")));
+    }
+    label
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_build_formatter() {
-        assert_eq!("csv", build_formatter(Format::Csv).name());
-        assert_eq!("default", build_formatter(Format::Default).name());
-        assert_eq!("json", build_formatter(Format::Json).name());
-        assert_eq!("markdown", build_formatter(Format::Markdown).name());
-        assert_eq!("xml", build_formatter(Format::Xml).name());
-        assert_eq!("yaml", build_formatter(Format::Yaml).name());
+        assert_eq!("csv", build_formatter(Format::Csv, false).name());
+        assert_eq!("default", build_formatter(Format::Default, false).name());
+        assert_eq!("json", build_formatter(Format::Json, false).name());
+        assert_eq!("markdown", build_formatter(Format::Markdown, false).name());
+        assert_eq!("xml", build_formatter(Format::Xml, false).name());
+        assert_eq!("yaml", build_formatter(Format::Yaml, false).name());
     }
 }
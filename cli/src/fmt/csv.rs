@@ -1,9 +1,18 @@
 use crate::defs;
-use crate::fmt::Formatter as FormatterTrait;
+use crate::fmt::{alternative_names, category_names, change_kind, Formatter as FormatterTrait};
+use btmeister::diff::{Change, Diff};
+use btmeister::history::{Commit, Timeline};
+use btmeister::policy::Report;
 use btmeister::{BuildTools, MeisterError, Result};
 use std::io::Write;
 
-pub(super) struct Formatter {}
+/// Formatter prints the results in CSV (RFC 4180).
+/// The build files are printed in three columns (base, path, and tool name) without the header row by default.
+/// The extended formatter prints the header row, and the version, wrapper, alternatives, confidence,
+/// and categories columns in addition.
+pub(super) struct Formatter {
+    pub(super) extended: bool,
+}
 
 impl FormatterTrait for Formatter {
     #[cfg(test)]
@@ -11,19 +20,24 @@ impl FormatterTrait for Formatter {
         "csv"
     }
     fn format_def(&self, def: &defs::BuildToolDef, _: bool) -> Result<String> {
-        let name = &def.name;
-        let url = &def.url;
+        let name = quote(&def.name);
+        let url = quote(&def.url);
+        let categories = quote(&category_names(def).join(";"));
         let result = def
             .build_files
             .iter()
-            .map(|s| format!("{},{},{}", name, s, url))
+            .map(|s| match self.extended {
+                true => format!("{},{},{},{}", name, quote(s), url, categories),
+                false => format!("{},{},{}", name, quote(s), url),
+            })
             .collect::<Vec<String>>()
             .join("\n");
         Ok(result)
     }
 
     fn header_defs(&self) -> Option<String> {
-        None
+        self.extended
+            .then(|| "name,build-file,url,categories".to_string())
     }
 
     fn footer_defs(&self) -> Option<String> {
@@ -32,24 +46,124 @@ impl FormatterTrait for Formatter {
 
     fn format_files(&self, tools: &BuildTools, _: bool) -> Result<String> {
         let mut result = Vec::<u8>::new();
-        let b = tools.base.display();
+        let b = quote(&tools.base.display().to_string());
         for bt in &tools.tools {
-            let _ = if let Ok(p) = bt.path.strip_prefix(tools.base.clone()) {
-                writeln!(result, "{},{},{}", b, p.display(), bt.def.name)
+            let path = if let Ok(p) = bt.path.strip_prefix(tools.base.clone()) {
+                p.display()
+            } else {
+                bt.path.display()
+            };
+            let path = quote(&path.to_string());
+            let _ = if self.extended {
+                writeln!(
+                    result,
+                    "{},{},{},{},{},{},{:.2},{}",
+                    b,
+                    path,
+                    quote(&bt.def.name),
+                    quote(&bt.version.clone().unwrap_or_default()),
+                    bt.wrapper,
+                    quote(&alternative_names(bt).join(";")),
+                    bt.confidence,
+                    quote(&category_names(&bt.def).join(";"))
+                )
             } else {
-                writeln!(result, "{},{},{}", b, bt.path.display(), bt.def.name)
+                writeln!(result, "{},{},{}", b, path, quote(&bt.def.name))
             };
         }
         String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
     }
 
+    fn format_history(&self, timeline: &Timeline, _: bool) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let columns = |c: Option<&Commit>| match c {
+            Some(c) => format!(
+                "{},{},{}",
+                c.id,
+                quote(&c.tag.clone().unwrap_or_default()),
+                c.date
+            ),
+            None => ",,".to_string(),
+        };
+        for span in &timeline.spans {
+            let _ = writeln!(
+                result,
+                "{},{},{},{}",
+                quote(&timeline.repository.display().to_string()),
+                quote(&span.name),
+                columns(Some(&span.appeared)),
+                columns(span.disappeared.as_ref())
+            );
+        }
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_diff(&self, diff: &Diff) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        for name in &diff.added_tools {
+            let _ = writeln!(result, "added-tool,{},,", quote(name));
+        }
+        for name in &diff.removed_tools {
+            let _ = writeln!(result, "removed-tool,{},,", quote(name));
+        }
+        for change in &diff.changes {
+            let (path, new_path) = match change {
+                Change::Added { path, .. } | Change::Removed { path, .. } => (path.as_str(), ""),
+                Change::Moved { from, to, .. } => (from.as_str(), to.as_str()),
+            };
+            let _ = writeln!(
+                result,
+                "{},{},{},{}",
+                change_kind(change),
+                quote(change.name()),
+                quote(path),
+                quote(new_path)
+            );
+        }
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_check(&self, report: &Report, _: bool) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        for v in &report.violations {
+            let _ = writeln!(
+                result,
+                "{},{},{},{}",
+                quote(&report.base.display().to_string()),
+                v.rule,
+                quote(&v.path.clone().unwrap_or_default()),
+                quote(&v.message)
+            );
+        }
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
     fn header_files(&self) -> Option<String> {
-        None
+        self.extended.then(|| {
+            "base,path,tool-name,version,wrapper,alternatives,confidence,categories".to_string()
+        })
     }
 
     fn footer_files(&self) -> Option<String> {
         None
     }
+
+    fn header_history(&self) -> Option<String> {
+        None
+    }
+
+    fn header_check(&self) -> Option<String> {
+        None
+    }
+}
+
+/// quote encloses the given field in double quotes if it contains commas, double quotes, or line breaks (RFC 4180).
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
 }
 
 #[cfg(test)]
@@ -58,7 +172,7 @@ mod tests {
 
     #[test]
     fn test_format_csv() {
-        let formatter = Formatter {};
+        let formatter = Formatter { extended: false };
         let def = crate::fmt::fake_build_def();
         let result = formatter.format_def(&def, false);
         assert!(result.is_ok());
@@ -71,7 +185,7 @@ mod tests {
 
     #[test]
     fn test_format_buildtools() {
-        let formatter = Formatter {};
+        let formatter = Formatter { extended: false };
         let tools = crate::fmt::fake_build_tools();
         let result = formatter.format_files(&tools, false);
         assert!(result.is_ok());
@@ -87,4 +201,134 @@ fake/base/dir,Makefile,Make
         assert_eq!(None, formatter.header_files());
         assert_eq!(None, formatter.footer_files());
     }
+
+    #[test]
+    fn test_format_extended() {
+        let formatter = Formatter { extended: true };
+        let mut tools = crate::fmt::fake_build_tools();
+        tools.tools[0].path = tools.base.join("a,b/\"Fakefile\"");
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"fake/base/dir,"a,b/""Fakefile""",Fake,,false,,0.00,
+fake/base/dir,Makefile,Make,,false,,0.00,
+"#
+                .to_string(),
+                r
+            );
+        }
+        assert_eq!(
+            Some(
+                "base,path,tool-name,version,wrapper,alternatives,confidence,categories"
+                    .to_string()
+            ),
+            formatter.header_files()
+        );
+        assert_eq!(
+            Some("name,build-file,url,categories".to_string()),
+            formatter.header_defs()
+        );
+        assert_eq!(None, formatter.header_history());
+    }
+
+    #[test]
+    fn test_format_pinned_version() {
+        let formatter = Formatter { extended: true };
+        let tools = crate::fmt::fake_pinned_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                "fake/base/dir,build.gradle,Gradle,8.5,true,,0.00,\n".to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_alternatives() {
+        let formatter = Formatter { extended: true };
+        let tools = crate::fmt::fake_multi_match_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                "fake/base/dir,BUILD,Fake,,false,Bazel;Pants,0.00,\n".to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_categories() {
+        let formatter = Formatter { extended: true };
+        let tools = crate::fmt::fake_categorized_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                "fake/base/dir,Makefile,Make,,false,,0.00,task-runner;build\n".to_string(),
+                r
+            );
+        }
+        let result = formatter.format_def(&tools.tools[0].def, false);
+        if let Ok(r) = result {
+            assert_eq!(
+                "Make,Makefile,https://example.com,task-runner;build".to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_history() {
+        let formatter = Formatter { extended: false };
+        let result = formatter.format_history(&crate::fmt::fake_timeline(), true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"fake/repo.git,Make,1111111111,v1,2020-01-01T00:00:00+09:00,2222222222,v2,2021-01-01T00:00:00+09:00
+fake/repo.git,Gradle,2222222222,v2,2021-01-01T00:00:00+09:00,,,
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_diff() {
+        let formatter = Formatter { extended: false };
+        let result = formatter.format_diff(&crate::fmt::fake_diff());
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"added-tool,Gradle,,
+removed-tool,Apache Maven,,
+moved,Make,Makefile,tools/Makefile
+added,Gradle,build.gradle,
+removed,Apache Maven,pom.xml,
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_check() {
+        let formatter = Formatter { extended: false };
+        let result = formatter.format_check(&crate::fmt::fake_report(), true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"fake/base/dir,deny,.travis.yml,Travis is denied
+fake/base/dir,require-one-of,,"none of GitHub Actions, GitLab CI/CD is used"
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
 }
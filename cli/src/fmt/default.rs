@@ -1,7 +1,10 @@
 use std::io::Write;
 
 use crate::defs;
-use crate::fmt::Formatter as FormatterTrait;
+use crate::fmt::{category_names, commit_label, tool_label, Formatter as FormatterTrait};
+use btmeister::diff::{Change, Diff};
+use btmeister::history::Timeline;
+use btmeister::policy::Report;
 use btmeister::{BuildTools, MeisterError, Result};
 
 pub(super) struct Formatter {}
@@ -13,7 +16,12 @@ impl FormatterTrait for Formatter {
     }
 
     fn format_def(&self, def: &defs::BuildToolDef, _: bool) -> Result<String> {
-        let name = &def.name;
+        let categories = category_names(def);
+        let name = if categories.is_empty() {
+            def.name.clone()
+        } else {
+            format!("{} [{}]", def.name, categories.join(", "))
+        };
         let result = def
             .build_files
             .iter()
@@ -35,11 +43,81 @@ impl FormatterTrait for Formatter {
         let mut result = Vec::<u8>::new();
         let _ = writeln!(result, "{}", tools.base.display());
         for bt in &tools.tools {
-            let _ = if let Ok(p) = bt.path.strip_prefix(tools.base.clone()) {
-                writeln!(result, "    {}: {}", p.display(), bt.def.name)
+            let path = if let Ok(p) = bt.path.strip_prefix(tools.base.clone()) {
+                p.display()
             } else {
-                writeln!(result, "    {}: {}", bt.path.display(), bt.def.name)
+                bt.path.display()
             };
+            let _ = writeln!(result, "    {}: {}", path, tool_label(bt));
+        }
+        if !tools.languages.is_empty() {
+            let languages = tools
+                .languages
+                .iter()
+                .map(|l| format!("{} ({})", l.name, l.files))
+                .collect::<Vec<String>>();
+            let _ = writeln!(result, "    languages: {}", languages.join(", "));
+        }
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_history(&self, timeline: &Timeline, _: bool) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let _ = writeln!(result, "{}", timeline.repository.display());
+        for span in &timeline.spans {
+            let disappeared = match &span.disappeared {
+                Some(c) => format!(" {}", commit_label(c)),
+                None => "".to_string(),
+            };
+            let _ = writeln!(
+                result,
+                "    {}: {} ..{}",
+                span.name,
+                commit_label(&span.appeared),
+                disappeared
+            );
+        }
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_diff(&self, diff: &Diff) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let _ = writeln!(
+            result,
+            "--- {}\n+++ {}",
+            diff.before.display(),
+            diff.after.display()
+        );
+        for name in &diff.added_tools {
+            let _ = writeln!(result, "+ {}", name);
+        }
+        for name in &diff.removed_tools {
+            let _ = writeln!(result, "- {}", name);
+        }
+        for change in &diff.changes {
+            let _ = match change {
+                Change::Added { name, path } => writeln!(result, "    + {}: {}", path, name),
+                Change::Removed { name, path } => writeln!(result, "    - {}: {}", path, name),
+                Change::Moved { name, from, to } => {
+                    writeln!(result, "    ~ {} -> {}: {}", from, to, name)
+                }
+            };
+        }
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_check(&self, report: &Report, _: bool) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let _ = writeln!(result, "{}", report.base.display());
+        if report.is_ok() {
+            let _ = writeln!(result, "    ok");
+        }
+        for v in &report.violations {
+            let path = match &v.path {
+                Some(p) => format!(" ({})", p),
+                None => "".to_string(),
+            };
+            let _ = writeln!(result, "    {}: {}{}", v.rule, v.message, path);
         }
         String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
     }
@@ -90,4 +168,124 @@ mod tests {
         assert_eq!(None, formatter.header_files());
         assert_eq!(None, formatter.footer_files());
     }
+
+    #[test]
+    fn test_format_pinned_version() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_pinned_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                "fake/base/dir\n    build.gradle: Gradle (version 8.5, wrapper)\n".to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_alternatives() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_multi_match_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                "fake/base/dir\n    BUILD: Fake (also: Bazel, Pants)\n".to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_categories() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_categorized_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                "fake/base/dir\n    Makefile: Make [task-runner, build]\n".to_string(),
+                r
+            );
+        }
+        let result = formatter.format_def(&tools.tools[0].def, false);
+        if let Ok(r) = result {
+            assert_eq!("Make [task-runner, build]: Makefile".to_string(), r);
+        }
+    }
+
+    #[test]
+    fn test_format_languages() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_language_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"fake/base/dir
+    Fakefile: Fake
+    Makefile: Make
+    languages: Rust (12), Shell (1)
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_history() {
+        let formatter = Formatter {};
+        let result = formatter.format_history(&crate::fmt::fake_timeline(), true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"fake/repo.git
+    Make: v1 (2020-01-01T00:00:00+09:00) .. v2 (2021-01-01T00:00:00+09:00)
+    Gradle: v2 (2021-01-01T00:00:00+09:00) ..
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_diff() {
+        let formatter = Formatter {};
+        let result = formatter.format_diff(&crate::fmt::fake_diff());
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"--- v1
++++ v2
++ Gradle
+- Apache Maven
+    ~ Makefile -> tools/Makefile: Make
+    + build.gradle: Gradle
+    - pom.xml: Apache Maven
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_check() {
+        let formatter = Formatter {};
+        let result = formatter.format_check(&crate::fmt::fake_report(), true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"fake/base/dir
+    deny: Travis is denied (.travis.yml)
+    require-one-of: none of GitHub Actions, GitLab CI/CD is used
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
 }
@@ -1,7 +1,12 @@
 use std::io::Write;
+use std::path::Path;
 
 use crate::defs;
-use crate::fmt::Formatter as FormatterTrait;
+use crate::fmt::{alternative_names, category_names, change_kind, Formatter as FormatterTrait};
+use btmeister::diff::{Change, Diff};
+use btmeister::history::{Commit, Timeline};
+use btmeister::policy::Report;
+use btmeister::watch::Event;
 use btmeister::{BuildTools, MeisterError, Result};
 
 pub(super) struct Formatter {}
@@ -27,10 +32,27 @@ impl FormatterTrait for crate::fmt::json::Formatter {
             .map(|s| format!("\"{}\"", s))
             .collect::<Vec<String>>()
             .join(",");
+        let contents = if def.contents.is_empty() {
+            "".to_string()
+        } else {
+            format!(
+                r#","contents":[{}]"#,
+                def.contents
+                    .iter()
+                    .map(|s| format!("\"{}\"", escape(s)))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            )
+        };
         let separator = if first { "" } else { "," };
         let result = format!(
-            r#"{}{{"name":"{}","build-files":[{}],"url":"{}"}}"#,
-            separator, &def.name, files, &def.url
+            r#"{}{{"name":"{}","build-files":[{}]{}{},"url":"{}"}}"#,
+            separator,
+            &def.name,
+            files,
+            contents,
+            categories(def),
+            &def.url
         );
         Ok(result)
     }
@@ -42,7 +64,7 @@ impl FormatterTrait for crate::fmt::json::Formatter {
             result,
             r#"{}{{"base":"{}","build-tools":["#,
             comma,
-            tools.base.display()
+            escape(&tools.base.display().to_string())
         );
         for (uindex, bt) in tools.tools.iter().enumerate() {
             let path = if let Ok(p) = bt.path.strip_prefix(tools.base.clone()) {
@@ -51,12 +73,160 @@ impl FormatterTrait for crate::fmt::json::Formatter {
                 bt.path.as_path()
             };
             let separator = if uindex == 0 { "" } else { "," };
+            let alternatives = alternative_names(bt);
+            let alternatives = if alternatives.is_empty() {
+                "".to_string()
+            } else {
+                format!(
+                    r#","alternatives":[{}]"#,
+                    alternatives
+                        .iter()
+                        .map(|s| format!("\"{}\"", escape(s)))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                )
+            };
+            let mut pinning = String::new();
+            if let Some(version) = &bt.version {
+                pinning.push_str(&format!(r#","version":"{}""#, escape(version)));
+            }
+            if bt.wrapper {
+                pinning.push_str(r#","wrapper":true"#);
+            }
+            if bt.confidence > 0.0 {
+                pinning.push_str(&format!(r#","confidence":{:.2}"#, bt.confidence));
+            }
+            let tasks = if bt.tasks.is_empty() {
+                "".to_string()
+            } else {
+                format!(
+                    r#","tasks":[{}]"#,
+                    bt.tasks
+                        .iter()
+                        .map(|s| format!("\"{}\"", escape(s)))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                )
+            };
+            let _ = writeln!(
+                result,
+                r#"{}{{"path":"{}","tool-name":"{}"{}{}{}{}}}"#,
+                separator,
+                escape(&path.display().to_string()),
+                escape(&bt.def.name),
+                categories(&bt.def),
+                pinning,
+                alternatives,
+                tasks
+            );
+        }
+        let languages = if tools.languages.is_empty() {
+            "".to_string()
+        } else {
+            format!(
+                r#","languages":{{{}}}"#,
+                tools
+                    .languages
+                    .iter()
+                    .map(|l| format!(r#""{}":{}"#, escape(&l.name), l.files))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            )
+        };
+        let _ = writeln!(result, "]{}}}", languages);
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_history(&self, timeline: &Timeline, first: bool) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let comma = if first { "" } else { "," };
+        let _ = writeln!(
+            result,
+            r#"{}{{"repository":"{}","timeline":["#,
+            comma,
+            escape(&timeline.repository.display().to_string())
+        );
+        for (index, span) in timeline.spans.iter().enumerate() {
+            let separator = if index == 0 { "" } else { "," };
+            let disappeared = match &span.disappeared {
+                Some(c) => format!(r#","disappeared":{}"#, commit(c)),
+                None => "".to_string(),
+            };
+            let _ = writeln!(
+                result,
+                r#"{}{{"tool-name":"{}","appeared":{}{}}}"#,
+                separator,
+                escape(&span.name),
+                commit(&span.appeared),
+                disappeared
+            );
+        }
+        let _ = writeln!(result, "]}}");
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_diff(&self, diff: &Diff) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let names = |names: &[String]| {
+            names
+                .iter()
+                .map(|s| format!("\"{}\"", escape(s)))
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+        let _ = writeln!(
+            result,
+            r#"{{"before":"{}","after":"{}","added-tools":[{}],"removed-tools":[{}],"changes":["#,
+            escape(&diff.before.display().to_string()),
+            escape(&diff.after.display().to_string()),
+            names(&diff.added_tools),
+            names(&diff.removed_tools)
+        );
+        for (index, change) in diff.changes.iter().enumerate() {
+            let separator = if index == 0 { "" } else { "," };
+            let paths = match change {
+                Change::Added { path, .. } | Change::Removed { path, .. } => {
+                    format!(r#""path":"{}""#, escape(path))
+                }
+                Change::Moved { from, to, .. } => {
+                    format!(r#""from":"{}","to":"{}""#, escape(from), escape(to))
+                }
+            };
+            let _ = writeln!(
+                result,
+                r#"{}{{"change":"{}","tool-name":"{}",{}}}"#,
+                separator,
+                change_kind(change),
+                escape(change.name()),
+                paths
+            );
+        }
+        let _ = writeln!(result, "]}}");
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_check(&self, report: &Report, first: bool) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let comma = if first { "" } else { "," };
+        let _ = writeln!(
+            result,
+            r#"{}{{"base":"{}","violations":["#,
+            comma,
+            escape(&report.base.display().to_string())
+        );
+        for (index, v) in report.violations.iter().enumerate() {
+            let separator = if index == 0 { "" } else { "," };
+            let path = match &v.path {
+                Some(p) => format!(r#","path":"{}""#, escape(p)),
+                None => "".to_string(),
+            };
             let _ = writeln!(
                 result,
-                r#"{}{{"path":"{}","tool-name":"{}"}}"#,
+                r#"{}{{"rule":"{}"{},"message":"{}"}}"#,
                 separator,
-                path.display(),
-                bt.def.name
+                v.rule,
+                path,
+                escape(&v.message)
             );
         }
         let _ = writeln!(result, "]}}");
@@ -72,6 +242,66 @@ impl FormatterTrait for crate::fmt::json::Formatter {
     }
 }
 
+/// categories returns the `"categories"` member of the given definition, or an empty string if it has no categories.
+fn categories(def: &defs::BuildToolDef) -> String {
+    let names = category_names(def);
+    if names.is_empty() {
+        "".to_string()
+    } else {
+        format!(
+            r#","categories":[{}]"#,
+            names
+                .iter()
+                .map(|s| format!("\"{}\"", s))
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
+
+/// commit returns the JSON object of the given commit.
+fn commit(c: &Commit) -> String {
+    let tag = match &c.tag {
+        Some(tag) => format!(r#","tag":"{}""#, escape(tag)),
+        None => "".to_string(),
+    };
+    format!(r#"{{"commit":"{}"{},"date":"{}"}}"#, c.id, tag, c.date)
+}
+
+/// event_line returns the given event of the watch mode in a line of JSON.
+pub(crate) fn event_line(base: &Path, event: &Event) -> String {
+    let (kind, bt) = match event {
+        Event::Added(bt) => ("added", bt),
+        Event::Removed(bt) => ("removed", bt),
+    };
+    let path = bt.path.strip_prefix(base).unwrap_or(&bt.path);
+    format!(
+        r#"{{"event":"{}","base":"{}","path":"{}","tool-name":"{}"}}"#,
+        kind,
+        escape(&base.display().to_string()),
+        escape(&path.display().to_string()),
+        escape(&bt.def.name)
+    )
+}
+
+/// escape escapes the backslashes, the double quotes, and the control characters in the given string
+/// for the JSON string literal.
+fn escape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,6 +340,21 @@ mod tests {
         assert_eq!(Some("]".to_string()), formatter.footer_defs());
     }
 
+    #[test]
+    fn test_format_def_with_contents() {
+        let formatter = Formatter {};
+        let def = fake_build_def().with_contents(vec![r#"re:name\s*=\s*"fake""#.to_string()]);
+        let result = formatter.format_def(&def, true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"{"name":"Fake","build-files":["Fakefile"],"contents":["re:name\\s*=\\s*\"fake\""],"url":"https://example.com"}"#
+                    .to_string(),
+                r
+            );
+        }
+    }
+
     #[test]
     fn test_format_buildtools() {
         let formatter = Formatter {};
@@ -130,4 +375,204 @@ mod tests {
         assert_eq!(Some("[".to_string()), formatter.header_files());
         assert_eq!(Some("]".to_string()), formatter.footer_files());
     }
+
+    #[test]
+    fn test_format_escaped_paths() {
+        let formatter = Formatter {};
+        let mut tools = crate::fmt::fake_build_tools();
+        tools.base = std::path::PathBuf::from(r#"C:\fake "base""#);
+        tools.tools[0].path = tools.base.join("Fake\tfile");
+        let r = formatter.format_files(&tools, true).unwrap();
+        let file =
+            std::env::temp_dir().join(format!("btmeister-escape-{}.json", std::process::id()));
+        std::fs::write(&file, format!("[{}]", r)).unwrap();
+        let loaded = btmeister::diff::load(&file).unwrap();
+        assert_eq!(tools.base, loaded[0].base);
+        assert_eq!("Fake\tfile", loaded[0].path_of(0).unwrap());
+        let _ = std::fs::remove_file(file);
+    }
+
+    #[test]
+    fn test_format_pinned_version() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_pinned_build_tools();
+        let result = formatter.format_files(&tools, true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"{"base":"fake/base/dir","build-tools":[
+{"path":"build.gradle","tool-name":"Gradle","version":"8.5","wrapper":true}
+]}
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_confidence() {
+        let formatter = Formatter {};
+        let mut tools = crate::fmt::fake_build_tools();
+        tools.tools[0].confidence = 0.625;
+        let result = formatter.format_files(&tools, true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"{"base":"fake/base/dir","build-tools":[
+{"path":"Fakefile","tool-name":"Fake","confidence":0.62}
+,{"path":"Makefile","tool-name":"Make"}
+]}
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_tasks() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_task_build_tools();
+        let result = formatter.format_files(&tools, true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"{"base":"fake/base/dir","build-tools":[
+{"path":"Makefile","tool-name":"Make","tasks":["build","test"]}
+]}
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_alternatives() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_multi_match_build_tools();
+        let result = formatter.format_files(&tools, true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"{"base":"fake/base/dir","build-tools":[
+{"path":"BUILD","tool-name":"Fake","alternatives":["Bazel","Pants"]}
+]}
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_categories() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_categorized_build_tools();
+        let result = formatter.format_files(&tools, true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"{"base":"fake/base/dir","build-tools":[
+{"path":"Makefile","tool-name":"Make","categories":["task-runner","build"]}
+]}
+"#
+                .to_string(),
+                r
+            );
+        }
+        let result = formatter.format_def(&tools.tools[0].def, true);
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"{"name":"Make","build-files":["Makefile"],"categories":["task-runner","build"],"url":"https://example.com"}"#
+                    .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_languages() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_language_build_tools();
+        let result = formatter.format_files(&tools, true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"{"base":"fake/base/dir","build-tools":[
+{"path":"Fakefile","tool-name":"Fake"}
+,{"path":"Makefile","tool-name":"Make"}
+],"languages":{"Rust":12,"Shell":1}}
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_history() {
+        let formatter = Formatter {};
+        let result = formatter.format_history(&crate::fmt::fake_timeline(), true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"{"repository":"fake/repo.git","timeline":[
+{"tool-name":"Make","appeared":{"commit":"1111111111","tag":"v1","date":"2020-01-01T00:00:00+09:00"},"disappeared":{"commit":"2222222222","tag":"v2","date":"2021-01-01T00:00:00+09:00"}}
+,{"tool-name":"Gradle","appeared":{"commit":"2222222222","tag":"v2","date":"2021-01-01T00:00:00+09:00"}}
+]}
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_diff() {
+        let formatter = Formatter {};
+        let result = formatter.format_diff(&crate::fmt::fake_diff());
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"{"before":"v1","after":"v2","added-tools":["Gradle"],"removed-tools":["Apache Maven"],"changes":[
+{"change":"moved","tool-name":"Make","from":"Makefile","to":"tools/Makefile"}
+,{"change":"added","tool-name":"Gradle","path":"build.gradle"}
+,{"change":"removed","tool-name":"Apache Maven","path":"pom.xml"}
+]}
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_check() {
+        let formatter = Formatter {};
+        let result = formatter.format_check(&crate::fmt::fake_report(), true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"{"base":"fake/base/dir","violations":[
+{"rule":"deny","path":".travis.yml","message":"Travis is denied"}
+,{"rule":"require-one-of","message":"none of GitHub Actions, GitLab CI/CD is used"}
+]}
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_event_line() {
+        let tools = crate::fmt::fake_build_tools();
+        let event = Event::Added(tools.tools[1].clone());
+        assert_eq!(
+            r#"{"event":"added","base":"fake/base/dir","path":"Makefile","tool-name":"Make"}"#,
+            event_line(&tools.base, &event)
+        );
+    }
 }
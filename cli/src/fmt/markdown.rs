@@ -1,5 +1,10 @@
 use crate::defs;
-use crate::fmt::Formatter as FormatterTrait;
+use crate::fmt::{
+    category_names, change_kind, commit_label, tool_label, Formatter as FormatterTrait,
+};
+use btmeister::diff::{Change, Diff};
+use btmeister::history::Timeline;
+use btmeister::policy::Report;
 use btmeister::{BuildTools, MeisterError, Result};
 use std::io::Write;
 
@@ -14,8 +19,17 @@ impl FormatterTrait for Formatter {
         let name = &def.name;
         let url = &def.url;
         let buildfiles = def.build_files.join("\n  - ");
+        let categories = category_names(def);
+        let categories = if categories.is_empty() {
+            "".to_string()
+        } else {
+            format!(" [{}]", categories.join(", "))
+        };
 
-        Ok(format!("- [{}]({})\n  - {}", name, url, buildfiles))
+        Ok(format!(
+            "- [{}]({}){}\n  - {}",
+            name, url, categories, buildfiles
+        ))
     }
 
     fn header_defs(&self) -> Option<String> {
@@ -31,11 +45,96 @@ impl FormatterTrait for Formatter {
         let b = tools.base.display();
         let _ = writeln!(result, "## {}\n", b);
         for bt in &tools.tools {
-            let _ = if let Ok(p) = bt.path.strip_prefix(tools.base.clone()) {
-                writeln!(result, "- {}\n  - {}", bt.def.name, p.display())
+            let path = if let Ok(p) = bt.path.strip_prefix(tools.base.clone()) {
+                p.display()
             } else {
-                writeln!(result, "- {}\n  - {}", bt.def.name, bt.path.display())
+                bt.path.display()
             };
+            let _ = writeln!(result, "- {}\n  - {}", tool_label(bt), path);
+        }
+        if !tools.languages.is_empty() {
+            let _ = writeln!(result, "\n### Languages\n");
+            for language in &tools.languages {
+                let _ = writeln!(result, "- {}: {}", language.name, language.files);
+            }
+        }
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_history(&self, timeline: &Timeline, _: bool) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let _ = writeln!(result, "## {}\n", timeline.repository.display());
+        let _ = writeln!(result, "| Build tool | Appeared | Disappeared |");
+        let _ = writeln!(result, "| --- | --- | --- |");
+        for span in &timeline.spans {
+            let disappeared = match &span.disappeared {
+                Some(c) => commit_label(c),
+                None => "".to_string(),
+            };
+            let _ = writeln!(
+                result,
+                "| {} | {} | {} |",
+                span.name,
+                commit_label(&span.appeared),
+                disappeared
+            );
+        }
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_diff(&self, diff: &Diff) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let _ = writeln!(
+            result,
+            "## {} -> {}\n",
+            diff.before.display(),
+            diff.after.display()
+        );
+        let _ = writeln!(
+            result,
+            "- Added build tools: {}",
+            diff.added_tools.join(", ")
+        );
+        let _ = writeln!(
+            result,
+            "- Removed build tools: {}\n",
+            diff.removed_tools.join(", ")
+        );
+        let _ = writeln!(result, "| Change | Build tool | Path |");
+        let _ = writeln!(result, "| --- | --- | --- |");
+        for change in &diff.changes {
+            let path = match change {
+                Change::Added { path, .. } | Change::Removed { path, .. } => path.clone(),
+                Change::Moved { from, to, .. } => format!("{} -> {}", from, to),
+            };
+            let _ = writeln!(
+                result,
+                "| {} | {} | {} |",
+                change_kind(change),
+                change.name(),
+                path
+            );
+        }
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_check(&self, report: &Report, _: bool) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let _ = writeln!(result, "## {}\n", report.base.display());
+        if report.is_ok() {
+            let _ = writeln!(result, "No violations.");
+        } else {
+            let _ = writeln!(result, "| Rule | Path | Message |");
+            let _ = writeln!(result, "| --- | --- | --- |");
+        }
+        for v in &report.violations {
+            let _ = writeln!(
+                result,
+                "| {} | {} | {} |",
+                v.rule,
+                v.path.clone().unwrap_or_default(),
+                v.message
+            );
         }
         String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
     }
@@ -88,4 +187,94 @@ mod tests {
         assert_eq!(None, formatter.header_files());
         assert_eq!(None, formatter.footer_files());
     }
+
+    #[test]
+    fn test_format_languages() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_language_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"## fake/base/dir
+
+- Fake
+  - Fakefile
+- Make
+  - Makefile
+
+### Languages
+
+- Rust: 12
+- Shell: 1
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_history() {
+        let formatter = Formatter {};
+        let result = formatter.format_history(&crate::fmt::fake_timeline(), true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"## fake/repo.git
+
+| Build tool | Appeared | Disappeared |
+| --- | --- | --- |
+| Make | v1 (2020-01-01T00:00:00+09:00) | v2 (2021-01-01T00:00:00+09:00) |
+| Gradle | v2 (2021-01-01T00:00:00+09:00) |  |
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_diff() {
+        let formatter = Formatter {};
+        let result = formatter.format_diff(&crate::fmt::fake_diff());
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"## v1 -> v2
+
+- Added build tools: Gradle
+- Removed build tools: Apache Maven
+
+| Change | Build tool | Path |
+| --- | --- | --- |
+| moved | Make | Makefile -> tools/Makefile |
+| added | Gradle | build.gradle |
+| removed | Apache Maven | pom.xml |
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_check() {
+        let formatter = Formatter {};
+        let result = formatter.format_check(&crate::fmt::fake_report(), true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"## fake/base/dir
+
+| Rule | Path | Message |
+| --- | --- | --- |
+| deny | .travis.yml | Travis is denied |
+| require-one-of |  | none of GitHub Actions, GitLab CI/CD is used |
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
 }
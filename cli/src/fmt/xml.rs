@@ -1,7 +1,10 @@
 use std::io::Write;
 
 use crate::defs;
-use crate::fmt::Formatter as FormatterTrait;
+use crate::fmt::{alternative_names, category_names, change_kind, Formatter as FormatterTrait};
+use btmeister::diff::{Change, Diff};
+use btmeister::history::{Commit, Timeline};
+use btmeister::policy::Report;
 use btmeister::{BuildTools, MeisterError, Result};
 
 pub(super) struct Formatter {}
@@ -24,17 +27,46 @@ impl FormatterTrait for Formatter {
         let files = &def
             .build_files
             .iter()
-            .map(|s| format!("            <build-file>{}</build-file>\n", s))
+            .map(|s| format!("            <build-file>{}</build-file>\n", escape(s)))
             .collect::<Vec<String>>()
             .concat();
+        let contents = if def.contents.is_empty() {
+            "".to_string()
+        } else {
+            format!(
+                "        <contents>\n{}        </contents>\n",
+                def.contents
+                    .iter()
+                    .map(|s| format!("            <content>{}</content>\n", escape(s)))
+                    .collect::<Vec<String>>()
+                    .concat()
+            )
+        };
+        let categories = category_names(def);
+        let categories = if categories.is_empty() {
+            "".to_string()
+        } else {
+            format!(
+                "        <categories>\n{}        </categories>\n",
+                categories
+                    .iter()
+                    .map(|s| format!("            <category>{}</category>\n", s))
+                    .collect::<Vec<String>>()
+                    .concat()
+            )
+        };
         let result = format!(
             r#"    <build-tool-def>
         <name>{}</name>
         <build-files>
 {}        </build-files>
-        <url>{}</url>
+{}{}        <url>{}</url>
     </build-tool-def>"#,
-            &def.name, files, &def.url
+            escape(&def.name),
+            files,
+            contents,
+            categories,
+            escape(&def.url)
         );
         Ok(result)
     }
@@ -45,7 +77,7 @@ impl FormatterTrait for Formatter {
             r#"    <project>
         <base-path>{}</base-path>
         <build-files>"#,
-            tools.base.display()
+            escape(&tools.base.display().to_string())
         );
         for bt in &tools.tools {
             let path_name = if let Ok(p) = bt.path.strip_prefix(tools.base.clone()) {
@@ -53,13 +85,161 @@ impl FormatterTrait for Formatter {
             } else {
                 bt.path.display()
             };
+            let mut attrs = String::new();
+            let categories = category_names(&bt.def);
+            if !categories.is_empty() {
+                attrs.push_str(&format!(r#" categories="{}""#, categories.join(",")));
+            }
+            if let Some(version) = &bt.version {
+                attrs.push_str(&format!(r#" version="{}""#, escape(version)));
+            }
+            if bt.wrapper {
+                attrs.push_str(r#" wrapper="true""#);
+            }
+            if bt.confidence > 0.0 {
+                attrs.push_str(&format!(r#" confidence="{:.2}""#, bt.confidence));
+            }
+            let alternatives = alternative_names(bt);
+            if !alternatives.is_empty() {
+                attrs.push_str(&format!(
+                    r#" alternatives="{}""#,
+                    escape(&alternatives.join(","))
+                ));
+            }
+            if !bt.tasks.is_empty() {
+                attrs.push_str(&format!(r#" tasks="{}""#, escape(&bt.tasks.join(","))));
+            }
             let _ = writeln!(
                 result,
-                r#"            <build-file tool-name="{}">{}</build-file>"#,
-                bt.def.name, path_name
+                r#"            <build-file tool-name="{}"{}>{}</build-file>"#,
+                escape(&bt.def.name),
+                attrs,
+                escape(&path_name.to_string())
             );
         }
-        let _ = writeln!(result, "        </build-files>\n    </project>");
+        let _ = writeln!(result, "        </build-files>");
+        if !tools.languages.is_empty() {
+            let _ = writeln!(result, "        <languages>");
+            for language in &tools.languages {
+                let _ = writeln!(
+                    result,
+                    r#"            <language name="{}" files="{}"/>"#,
+                    escape(&language.name),
+                    language.files
+                );
+            }
+            let _ = writeln!(result, "        </languages>");
+        }
+        let _ = writeln!(result, "    </project>");
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn header_history(&self) -> Option<String> {
+        Some("<? xml version=\"1.0\" ?>\n<histories>".to_string())
+    }
+
+    fn footer_history(&self) -> Option<String> {
+        Some("</histories>".to_string())
+    }
+
+    fn format_history(&self, timeline: &Timeline, _: bool) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let _ = writeln!(
+            result,
+            "    <history>\n        <repository>{}</repository>\n        <timeline>",
+            escape(&timeline.repository.display().to_string())
+        );
+        for span in &timeline.spans {
+            let _ = writeln!(
+                result,
+                r#"            <span tool-name="{}">"#,
+                escape(&span.name)
+            );
+            let _ = writeln!(result, "{}", commit("appeared", &span.appeared));
+            if let Some(c) = &span.disappeared {
+                let _ = writeln!(result, "{}", commit("disappeared", c));
+            }
+            let _ = writeln!(result, "            </span>");
+        }
+        let _ = writeln!(result, "        </timeline>\n    </history>");
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_diff(&self, diff: &Diff) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let _ = writeln!(
+            result,
+            "<? xml version=\"1.0\" ?>\n<diff before=\"{}\" after=\"{}\">",
+            escape(&diff.before.display().to_string()),
+            escape(&diff.after.display().to_string())
+        );
+        for (key, names) in [
+            ("added-tools", &diff.added_tools),
+            ("removed-tools", &diff.removed_tools),
+        ] {
+            let _ = writeln!(result, "    <{}>", key);
+            for name in names {
+                let _ = writeln!(result, "        <tool-name>{}</tool-name>", escape(name));
+            }
+            let _ = writeln!(result, "    </{}>", key);
+        }
+        let _ = writeln!(result, "    <changes>");
+        for change in &diff.changes {
+            let kind = change_kind(change);
+            let name = escape(change.name());
+            let _ = match change {
+                Change::Added { path, .. } | Change::Removed { path, .. } => writeln!(
+                    result,
+                    r#"        <{} tool-name="{}">{}</{}>"#,
+                    kind,
+                    name,
+                    escape(path),
+                    kind
+                ),
+                Change::Moved { from, to, .. } => writeln!(
+                    result,
+                    r#"        <{} tool-name="{}" from="{}">{}</{}>"#,
+                    kind,
+                    name,
+                    escape(from),
+                    escape(to),
+                    kind
+                ),
+            };
+        }
+        let _ = writeln!(result, "    </changes>\n</diff>");
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn header_check(&self) -> Option<String> {
+        Some("<? xml version=\"1.0\" ?>\n<checks>".to_string())
+    }
+
+    fn footer_check(&self) -> Option<String> {
+        Some("</checks>".to_string())
+    }
+
+    fn format_check(&self, report: &Report, _: bool) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let _ = writeln!(
+            result,
+            "    <check>\n        <base-path>{}</base-path>\n        <violations>",
+            escape(&report.base.display().to_string())
+        );
+        for v in &report.violations {
+            let path = match &v.path {
+                Some(p) => format!(r#" path="{}""#, escape(p)),
+                None => "".to_string(),
+            };
+            let _ = writeln!(
+                result,
+                r#"            <violation rule="{}"{}>{}</violation>"#,
+                v.rule,
+                path,
+                escape(&v.message)
+            );
+        }
+        let _ = writeln!(result, "        </violations>\n    </check>");
         String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
     }
 
@@ -72,6 +252,26 @@ impl FormatterTrait for Formatter {
     }
 }
 
+/// commit returns the element of the given commit with the given element name.
+fn commit(name: &str, c: &Commit) -> String {
+    let tag = match &c.tag {
+        Some(tag) => format!(r#" tag="{}""#, escape(tag)),
+        None => "".to_string(),
+    };
+    format!(
+        r#"                <{} commit="{}"{} date="{}"/>"#,
+        name, c.id, tag, c.date
+    )
+}
+
+/// escape escapes the special characters in the given string for the XML text.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,6 +332,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_format_pinned_version() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_pinned_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"    <project>
+        <base-path>fake/base/dir</base-path>
+        <build-files>
+            <build-file tool-name="Gradle" version="8.5" wrapper="true">build.gradle</build-file>
+        </build-files>
+    </project>
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_tasks() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_task_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"    <project>
+        <base-path>fake/base/dir</base-path>
+        <build-files>
+            <build-file tool-name="Make" tasks="build,test">Makefile</build-file>
+        </build-files>
+    </project>
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
     #[test]
     fn test_header_and_footer2() {
         let formatter = Formatter {};
@@ -141,4 +383,171 @@ mod tests {
         );
         assert_eq!(Some("</build-tools>".to_string()), formatter.footer_files());
     }
+
+    #[test]
+    fn test_format_categories() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_categorized_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"    <project>
+        <base-path>fake/base/dir</base-path>
+        <build-files>
+            <build-file tool-name="Make" categories="task-runner,build">Makefile</build-file>
+        </build-files>
+    </project>
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_languages() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_language_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"    <project>
+        <base-path>fake/base/dir</base-path>
+        <build-files>
+            <build-file tool-name="Fake">Fakefile</build-file>
+            <build-file tool-name="Make">Makefile</build-file>
+        </build-files>
+        <languages>
+            <language name="Rust" files="12"/>
+            <language name="Shell" files="1"/>
+        </languages>
+    </project>
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_escaped_names() {
+        let formatter = Formatter {};
+        let def = |name: &str| {
+            defs::BuildToolDef::new(
+                name.to_string(),
+                vec!["a&b.mk".to_string()],
+                "https://example.com/?a=1&b=2".to_string(),
+            )
+        };
+        let mut bt = btmeister::BuildTool::new("fake/base/dir/a&b.mk".into(), def("R&D <make>"));
+        bt.alternatives = vec![def("\"quoted\"")];
+        let tools = BuildTools::new(std::path::PathBuf::from("fake/base/dir"), vec![bt]);
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"    <project>
+        <base-path>fake/base/dir</base-path>
+        <build-files>
+            <build-file tool-name="R&amp;D &lt;make&gt;" alternatives="&quot;quoted&quot;">a&amp;b.mk</build-file>
+        </build-files>
+    </project>
+"#
+                .to_string(),
+                r
+            );
+        }
+        let result = formatter.format_def(&def("R&D <make>"), false);
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"    <build-tool-def>
+        <name>R&amp;D &lt;make&gt;</name>
+        <build-files>
+            <build-file>a&amp;b.mk</build-file>
+        </build-files>
+        <url>https://example.com/?a=1&amp;b=2</url>
+    </build-tool-def>"#
+                    .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_history() {
+        let formatter = Formatter {};
+        let result = formatter.format_history(&crate::fmt::fake_timeline(), true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"    <history>
+        <repository>fake/repo.git</repository>
+        <timeline>
+            <span tool-name="Make">
+                <appeared commit="1111111111" tag="v1" date="2020-01-01T00:00:00+09:00"/>
+                <disappeared commit="2222222222" tag="v2" date="2021-01-01T00:00:00+09:00"/>
+            </span>
+            <span tool-name="Gradle">
+                <appeared commit="2222222222" tag="v2" date="2021-01-01T00:00:00+09:00"/>
+            </span>
+        </timeline>
+    </history>
+"#
+                .to_string(),
+                r
+            );
+        }
+        assert_eq!(Some("</histories>".to_string()), formatter.footer_history());
+    }
+
+    #[test]
+    fn test_format_diff() {
+        let formatter = Formatter {};
+        let result = formatter.format_diff(&crate::fmt::fake_diff());
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"<? xml version="1.0" ?>
+<diff before="v1" after="v2">
+    <added-tools>
+        <tool-name>Gradle</tool-name>
+    </added-tools>
+    <removed-tools>
+        <tool-name>Apache Maven</tool-name>
+    </removed-tools>
+    <changes>
+        <moved tool-name="Make" from="Makefile">tools/Makefile</moved>
+        <added tool-name="Gradle">build.gradle</added>
+        <removed tool-name="Apache Maven">pom.xml</removed>
+    </changes>
+</diff>
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_check() {
+        let formatter = Formatter {};
+        let result = formatter.format_check(&crate::fmt::fake_report(), true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"    <check>
+        <base-path>fake/base/dir</base-path>
+        <violations>
+            <violation rule="deny" path=".travis.yml">Travis is denied</violation>
+            <violation rule="require-one-of">none of GitHub Actions, GitLab CI/CD is used</violation>
+        </violations>
+    </check>
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
 }
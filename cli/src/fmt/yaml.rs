@@ -1,7 +1,10 @@
 use std::io::Write;
 
 use crate::defs;
-use crate::fmt::Formatter as FormatterTrait;
+use crate::fmt::{alternative_names, category_names, change_kind, Formatter as FormatterTrait};
+use btmeister::diff::{Change, Diff};
+use btmeister::history::{Commit, Timeline};
+use btmeister::policy::Report;
 use btmeister::{BuildTools, MeisterError, Result};
 
 pub(super) struct Formatter {}
@@ -27,12 +30,37 @@ impl FormatterTrait for Formatter {
             .map(|s| format!("  - {}", s))
             .collect::<Vec<String>>()
             .join("\n");
+        let contents = if def.contents.is_empty() {
+            "".to_string()
+        } else {
+            format!(
+                "\n  contents:\n{}",
+                def.contents
+                    .iter()
+                    .map(|s| format!("  - '{}'", s.replace('\'', "''")))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            )
+        };
+        let categories = category_names(def);
+        let categories = if categories.is_empty() {
+            "".to_string()
+        } else {
+            format!(
+                "\n  categories:\n{}",
+                categories
+                    .iter()
+                    .map(|s| format!("  - {}", s))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            )
+        };
         Ok(format!(
             r#"- name: {}
   build-files:
-{}
+{}{}{}
   url: {}"#,
-            &def.name, files, &def.url
+            &def.name, files, contents, categories, &def.url
         ))
     }
     fn format_files(&self, tools: &BuildTools, _: bool) -> Result<String> {
@@ -55,6 +83,133 @@ impl FormatterTrait for Formatter {
     file-path: {}"#,
                 bt.def.name, path_name
             );
+            let categories = category_names(&bt.def);
+            if !categories.is_empty() {
+                let _ = writeln!(result, "    categories:");
+                for name in categories {
+                    let _ = writeln!(result, "    - {}", name);
+                }
+            }
+            if let Some(version) = &bt.version {
+                let _ = writeln!(result, "    version: '{}'", version.replace('\'', "''"));
+            }
+            if bt.wrapper {
+                let _ = writeln!(result, "    wrapper: true");
+            }
+            if bt.confidence > 0.0 {
+                let _ = writeln!(result, "    confidence: {:.2}", bt.confidence);
+            }
+            let alternatives = alternative_names(bt);
+            if !alternatives.is_empty() {
+                let _ = writeln!(result, "    alternatives:");
+                for name in alternatives {
+                    let _ = writeln!(result, "    - {}", name);
+                }
+            }
+            if !bt.tasks.is_empty() {
+                let _ = writeln!(result, "    tasks:");
+                for task in &bt.tasks {
+                    let _ = writeln!(result, "    - '{}'", task.replace('\'', "''"));
+                }
+            }
+        }
+        if !tools.languages.is_empty() {
+            let _ = writeln!(result, "  languages:");
+            for language in &tools.languages {
+                let _ = writeln!(
+                    result,
+                    "  - name: '{}'\n    files: {}",
+                    language.name.replace('\'', "''"),
+                    language.files
+                );
+            }
+        }
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_history(&self, timeline: &Timeline, _: bool) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let _ = writeln!(
+            result,
+            "- repository: {}\n  timeline:",
+            timeline.repository.display()
+        );
+        let commit = |result: &mut Vec<u8>, key: &str, c: &Commit| {
+            let _ = writeln!(result, "    {}:\n      commit: '{}'", key, c.id);
+            if let Some(tag) = &c.tag {
+                let _ = writeln!(result, "      tag: '{}'", tag.replace('\'', "''"));
+            }
+            let _ = writeln!(result, "      date: '{}'", c.date);
+        };
+        for span in &timeline.spans {
+            let _ = writeln!(result, "  - tool-name: {}", span.name);
+            commit(&mut result, "appeared", &span.appeared);
+            if let Some(c) = &span.disappeared {
+                commit(&mut result, "disappeared", c);
+            }
+        }
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_diff(&self, diff: &Diff) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let _ = writeln!(
+            result,
+            "before: {}\nafter: {}",
+            diff.before.display(),
+            diff.after.display()
+        );
+        for (key, names) in [
+            ("added-tools", &diff.added_tools),
+            ("removed-tools", &diff.removed_tools),
+        ] {
+            if names.is_empty() {
+                let _ = writeln!(result, "{}: []", key);
+            } else {
+                let _ = writeln!(result, "{}:", key);
+                for name in names {
+                    let _ = writeln!(result, "- {}", name);
+                }
+            }
+        }
+        if diff.changes.is_empty() {
+            let _ = writeln!(result, "changes: []");
+        } else {
+            let _ = writeln!(result, "changes:");
+        }
+        for change in &diff.changes {
+            let _ = writeln!(
+                result,
+                "- change: {}\n  tool-name: {}",
+                change_kind(change),
+                change.name()
+            );
+            let _ = match change {
+                Change::Added { path, .. } | Change::Removed { path, .. } => {
+                    writeln!(result, "  path: {}", path)
+                }
+                Change::Moved { from, to, .. } => {
+                    writeln!(result, "  from: {}\n  to: {}", from, to)
+                }
+            };
+        }
+        String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
+    }
+
+    fn format_check(&self, report: &Report, _: bool) -> Result<String> {
+        let mut result = Vec::<u8>::new();
+        let _ = writeln!(result, "- project: {}", report.base.display());
+        if report.is_ok() {
+            let _ = writeln!(result, "  violations: []");
+        } else {
+            let _ = writeln!(result, "  violations:");
+        }
+        for v in &report.violations {
+            let _ = writeln!(result, "  - rule: {}", v.rule);
+            if let Some(path) = &v.path {
+                let _ = writeln!(result, "    path: {}", path);
+            }
+            let _ = writeln!(result, "    message: '{}'", v.message.replace('\'', "''"));
         }
         String::from_utf8(result).map_err(|e| MeisterError::Fatal(format!("{}", e)))
     }
@@ -115,4 +270,213 @@ mod tests {
         assert_eq!(None, formatter.header_files());
         assert_eq!(None, formatter.footer_files());
     }
+
+    #[test]
+    fn test_format_pinned_version() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_pinned_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"- project: fake/base/dir
+  build-files:
+  - tool-name: Gradle
+    file-path: build.gradle
+    version: '8.5'
+    wrapper: true
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_tasks() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_task_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"- project: fake/base/dir
+  build-files:
+  - tool-name: Make
+    file-path: Makefile
+    tasks:
+    - 'build'
+    - 'test'
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_alternatives() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_multi_match_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"- project: fake/base/dir
+  build-files:
+  - tool-name: Fake
+    file-path: BUILD
+    alternatives:
+    - Bazel
+    - Pants
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_categories() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_categorized_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"- project: fake/base/dir
+  build-files:
+  - tool-name: Make
+    file-path: Makefile
+    categories:
+    - task-runner
+    - build
+"#
+                .to_string(),
+                r
+            );
+        }
+        let result = formatter.format_def(&tools.tools[0].def, false);
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"- name: Make
+  build-files:
+  - Makefile
+  categories:
+  - task-runner
+  - build
+  url: https://example.com"#
+                    .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_languages() {
+        let formatter = Formatter {};
+        let tools = crate::fmt::fake_language_build_tools();
+        let result = formatter.format_files(&tools, false);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"- project: fake/base/dir
+  build-files:
+  - tool-name: Fake
+    file-path: Fakefile
+  - tool-name: Make
+    file-path: Makefile
+  languages:
+  - name: 'Rust'
+    files: 12
+  - name: 'Shell'
+    files: 1
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_history() {
+        let formatter = Formatter {};
+        let result = formatter.format_history(&crate::fmt::fake_timeline(), true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"- repository: fake/repo.git
+  timeline:
+  - tool-name: Make
+    appeared:
+      commit: '1111111111'
+      tag: 'v1'
+      date: '2020-01-01T00:00:00+09:00'
+    disappeared:
+      commit: '2222222222'
+      tag: 'v2'
+      date: '2021-01-01T00:00:00+09:00'
+  - tool-name: Gradle
+    appeared:
+      commit: '2222222222'
+      tag: 'v2'
+      date: '2021-01-01T00:00:00+09:00'
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_diff() {
+        let formatter = Formatter {};
+        let result = formatter.format_diff(&crate::fmt::fake_diff());
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"before: v1
+after: v2
+added-tools:
+- Gradle
+removed-tools:
+- Apache Maven
+changes:
+- change: moved
+  tool-name: Make
+  from: Makefile
+  to: tools/Makefile
+- change: added
+  tool-name: Gradle
+  path: build.gradle
+- change: removed
+  tool-name: Apache Maven
+  path: pom.xml
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_check() {
+        let formatter = Formatter {};
+        let result = formatter.format_check(&crate::fmt::fake_report(), true);
+        assert!(result.is_ok());
+        if let Ok(r) = result {
+            assert_eq!(
+                r#"- project: fake/base/dir
+  violations:
+  - rule: deny
+    path: .travis.yml
+    message: 'Travis is denied'
+  - rule: require-one-of
+    message: 'none of GitHub Actions, GitLab CI/CD is used'
+"#
+                .to_string(),
+                r
+            );
+        }
+    }
 }
@@ -1,12 +1,16 @@
 mod cli;
 mod fmt;
 
-use crate::cli::InputOpts;
+use crate::cli::{Format, InputOpts};
 use crate::fmt::Formatter;
 use btmeister::defs::{self, BuildToolDefs};
-use btmeister::{BuildTools, Meister, MeisterError};
+use btmeister::diff::Diff;
+use btmeister::langs::{self, LanguageDefs};
+use btmeister::policy::Policy;
+use btmeister::{BuildTools, Meister, MeisterError, ToolFilter};
 use btmeister::{LogLevel, Result};
 use clap::Parser;
+use std::path::{Component, Path, PathBuf};
 
 fn list_defs(defs: BuildToolDefs, f: Box<dyn Formatter>) -> Result<()> {
     if let Some(header) = f.header_defs() {
@@ -52,17 +56,317 @@ fn print_results(r: Vec<BuildTools>, f: Box<dyn Formatter>) -> Result<()> {
     }
 }
 
-fn find_bt(defs: BuildToolDefs, opts: InputOpts) -> Result<Vec<BuildTools>> {
-    let meister = Meister::new(defs, opts.ignore_types.clone())?;
+fn build_meister(
+    defs: BuildToolDefs,
+    languages: Option<LanguageDefs>,
+    opts: &InputOpts,
+) -> Result<Meister> {
+    let meister =
+        Meister::new_with_excludes(defs, opts.ignore_types.clone(), opts.excludes.clone())?
+            .with_threads(opts.threads)
+            .with_multi_match(opts.multi_match)
+            .with_tasks(opts.tasks)
+            .with_versions(opts.versions)
+            .with_archive_depth(opts.archive_depth)
+            .with_open_archives(opts.open_archives)
+            .with_tool_filter(ToolFilter::new(
+                opts.only.clone(),
+                opts.exclude_tool.clone(),
+            ))?;
+    let meister = match &opts.cache {
+        Some(cache) => meister.with_cache(cache.clone()),
+        None => meister,
+    };
+    match languages {
+        Some(languages) => Ok(meister.with_languages(languages)),
+        None => Ok(meister),
+    }
+}
+
+fn print_histories(defs: BuildToolDefs, opts: InputOpts, f: Box<dyn Formatter>) -> Result<()> {
+    let meister = build_meister(defs, None, &opts)?;
+    let mut errs = vec![];
+    if let Some(header) = f.header_history() {
+        println!("{}", header);
+    }
+    for (i, project) in opts.projects()?.into_iter().enumerate() {
+        match meister
+            .history(project, opts.tags_only)
+            .and_then(|t| f.format_history(&t, i == 0))
+        {
+            Ok(s) => print!("{}", s),
+            Err(e) => errs.push(e),
+        }
+    }
+    if let Some(footer) = f.footer_history() {
+        println!("{}", footer);
+    }
+    if errs.is_empty() {
+        Ok(())
+    } else {
+        Err(MeisterError::Array(errs))
+    }
+}
+
+/// scan_or_load detects the build tools in the given project, or loads the saved result if a JSON file was given.
+fn scan_or_load(meister: &Meister, item: &str) -> Result<BuildTools> {
+    let path = PathBuf::from(item);
+    if path.is_file() && item.to_lowercase().ends_with(".json") {
+        let mut results = btmeister::diff::load(&path)?;
+        if results.len() == 1 {
+            Ok(results.remove(0))
+        } else {
+            Err(MeisterError::Fatal(format!(
+                "{}: the saved result must contain exactly one project",
+                item
+            )))
+        }
+    } else {
+        // the top-level directory wrapping the project in the archive file is removed from the paths,
+        // to compare the archive file with the directory of the same project.
+        let mut results = meister.find_projects(path.clone())?;
+        if results.len() == 1 {
+            Ok(results.remove(0))
+        } else {
+            meister.find(path)
+        }
+    }
+}
+
+fn find_diff(meister: &Meister, before: &str, after: &str) -> Result<Diff> {
+    let before = scan_or_load(meister, before)?;
+    let after = scan_or_load(meister, after)?;
+    Ok(btmeister::diff::diff(&before, &after))
+}
+
+fn print_diff(defs: BuildToolDefs, opts: InputOpts, f: Box<dyn Formatter>) -> Result<()> {
+    let (before, after) = match opts.dirs.as_slice() {
+        [before, after] => (before, after),
+        _ => {
+            return Err(MeisterError::Fatal(
+                "--diff requires exactly two projects".to_string(),
+            ))
+        }
+    };
+    let meister = build_meister(defs, None, &opts)?;
+    print!("{}", f.format_diff(&find_diff(&meister, before, after)?)?);
+    Ok(())
+}
+
+fn print_checks(
+    defs: BuildToolDefs,
+    policy: Policy,
+    opts: InputOpts,
+    f: Box<dyn Formatter>,
+) -> Result<()> {
+    let results = find_bt(defs, None, opts)?;
+    let mut errs = vec![];
+    let mut failed = 0;
+    if let Some(header) = f.header_check() {
+        println!("{}", header);
+    }
+    for (i, bt) in results.iter().enumerate() {
+        let report = policy.check(bt);
+        if !report.is_ok() {
+            failed += 1;
+        }
+        match f.format_check(&report, i == 0) {
+            Ok(s) => print!("{}", s),
+            Err(e) => errs.push(e),
+        }
+    }
+    if let Some(footer) = f.footer_check() {
+        println!("{}", footer);
+    }
+    if !errs.is_empty() {
+        Err(MeisterError::Array(errs))
+    } else if failed > 0 {
+        Err(MeisterError::PolicyViolation(failed))
+    } else {
+        Ok(())
+    }
+}
+
+/// format_results returns the given results in the given format as a string.
+fn format_results(r: &[BuildTools], f: Box<dyn Formatter>) -> Result<String> {
+    let mut result = String::new();
+    if let Some(header) = f.header_files() {
+        result.push_str(&format!("{}\n", header));
+    }
+    for (i, bt) in r.iter().enumerate() {
+        result.push_str(&f.format_files(bt, i == 0)?);
+    }
+    if let Some(footer) = f.footer_files() {
+        result.push_str(&format!("{}\n", footer));
+    }
+    Ok(result)
+}
+
+fn print_expectation(
+    defs: BuildToolDefs,
+    expect: PathBuf,
+    opts: InputOpts,
+    f: Box<dyn Formatter>,
+) -> Result<()> {
+    let update = opts.update_expect;
+    let results = find_bt(defs, None, opts)?;
+    let dir = normalize(&expect)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    if update {
+        let snapshot = results
+            .iter()
+            .map(|r| snapshot_of(r, &dir))
+            .collect::<Result<Vec<BuildTools>>>()?;
+        let json = format_results(&snapshot, fmt::build_formatter(Format::Json, false))?;
+        return std::fs::write(&expect, json).map_err(MeisterError::IO);
+    }
+    let expected = btmeister::diff::load(&expect)?;
+    let mut found = vec![false; expected.len()];
+    let mut diffs = vec![];
+    for actual in results.iter() {
+        let base = normalize(&actual.base);
+        let diff = match expected
+            .iter()
+            .position(|e| normalize(&dir.join(&e.base)) == base)
+        {
+            Some(index) => {
+                found[index] = true;
+                btmeister::diff::diff(&expected[index], actual)
+            }
+            None => btmeister::diff::diff(&BuildTools::new(expect.clone(), vec![]), actual),
+        };
+        if !diff.is_empty() {
+            diffs.push(diff);
+        }
+    }
+    // the expected projects missing in the current result are reported as the removals of all of their build files.
+    for (e, _) in expected.iter().zip(found).filter(|(_, found)| !found) {
+        diffs.push(btmeister::diff::diff(
+            e,
+            &BuildTools::new(dir.join(&e.base), vec![]),
+        ));
+    }
+    for diff in diffs.iter() {
+        print!("{}", f.format_diff(diff)?);
+    }
+    if !diffs.is_empty() {
+        Err(MeisterError::Fatal(format!(
+            "{}: the detected build tools differ from the expectation",
+            expect.display()
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// snapshot_of returns the given result with the base relative to the given directory (of the expectation file),
+/// and the paths of the build files relative to the base,
+/// so that the expectation file does not depend on the current directory.
+fn snapshot_of(r: &BuildTools, dir: &Path) -> Result<BuildTools> {
+    let mut snapshot = r.clone();
+    for (index, bt) in snapshot.tools.iter_mut().enumerate() {
+        bt.path = PathBuf::from(r.path_of(index)?);
+    }
+    snapshot.base = relative_path(&normalize(&r.base), dir);
+    Ok(snapshot)
+}
+
+/// normalize returns the absolute path of the given path, removing `.` and `..` components lexically.
+fn normalize(path: &Path) -> PathBuf {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut result = PathBuf::new();
+    for c in path.components() {
+        match c {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                result.pop();
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// relative_path returns the path of `path` relative to `dir`. Both of them must be normalized.
+fn relative_path(path: &Path, dir: &Path) -> PathBuf {
+    let common = path
+        .components()
+        .zip(dir.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut result = PathBuf::new();
+    for _ in dir.components().skip(common) {
+        result.push("..");
+    }
+    result.extend(path.components().skip(common));
+    if result.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        result
+    }
+}
+
+fn print_watch(defs: BuildToolDefs, opts: InputOpts) -> Result<()> {
+    use std::io::Write;
+    let meister = build_meister(defs, None, &opts)?;
+    let projects = opts.projects()?;
+    let (tx, rx) = std::sync::mpsc::channel::<String>();
+    let errs = std::thread::scope(|s| {
+        let handles = projects
+            .into_iter()
+            .map(|project| {
+                let (meister, tx) = (meister.clone(), tx.clone());
+                s.spawn(move || {
+                    meister.watch(project.clone(), |event| {
+                        tx.send(fmt::event_line(&project, &event)).is_ok()
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(tx);
+        for line in rx {
+            println!("{}", line);
+            let _ = std::io::stdout().flush();
+        }
+        handles
+            .into_iter()
+            .filter_map(|h| match h.join() {
+                Ok(r) => r.err(),
+                Err(_) => Some(MeisterError::Fatal("watch: thread panicked".to_string())),
+            })
+            .collect::<Vec<MeisterError>>()
+    });
+    if errs.is_empty() {
+        Ok(())
+    } else {
+        Err(MeisterError::Array(errs))
+    }
+}
+
+fn find_bt(
+    defs: BuildToolDefs,
+    languages: Option<LanguageDefs>,
+    opts: InputOpts,
+) -> Result<Vec<BuildTools>> {
+    let meister = build_meister(defs, languages, &opts)?;
     let mut errs = vec![];
     let mut result = vec![];
     match opts.projects() {
         Err(e) => return Err(e),
         Ok(projects) => {
             for project in projects {
-                match meister.find(project) {
-                    Ok(r) => result.push(r),
-                    Err(e) => errs.push(e),
+                if opts.archive_roots {
+                    match meister.find_projects(project) {
+                        Ok(r) => result.extend(r),
+                        Err(e) => errs.push(e),
+                    }
+                } else {
+                    match meister.find(project) {
+                        Ok(r) => result.push(r),
+                        Err(e) => errs.push(e),
+                    }
                 }
             }
         }
@@ -126,19 +430,48 @@ fn perform(opts: cli::Options) -> Result<()> {
     let (input_opts, output_opts, defopts) = (opts.inputs, opts.outputs, opts.defopts);
     #[cfg(debug_assertions)]
     let compopts = opts.compopts;
-    let defs = defs::construct(defopts.definition, defopts.append_defs)?;
+    let mut defs = defs::construct(defopts.definition, defopts.append_defs)?;
+    ToolFilter::new(input_opts.only.clone(), input_opts.exclude_tool.clone()).validate(&defs)?;
+    let policy = match input_opts.check.clone() {
+        Some(path) => Some(Policy::parse(path)?),
+        None => None,
+    };
+    if let Some(policy) = &policy {
+        policy.validate(&defs)?;
+    }
+    defs.retain_categories(&defopts.categories, &defopts.exclude_categories);
     if cfg!(debug_assertions) {
         #[cfg(debug_assertions)]
         if compopts.completion {
             return gencomp::generate_completions(compopts.dest);
         }
     }
-    let formatter = fmt::build_formatter(output_opts.format);
+    let formatter = fmt::build_formatter(output_opts.format, output_opts.csv_extended);
     if output_opts.list_defs {
         list_defs(defs, formatter)
+    } else if input_opts.history {
+        print_histories(defs, input_opts, formatter)
+    } else if input_opts.diff {
+        print_diff(defs, input_opts, formatter)
+    } else if input_opts.watch {
+        print_watch(defs, input_opts)
+    } else if let Some(path) = input_opts.expect.clone() {
+        print_expectation(defs, path, input_opts, formatter)
+    } else if let Some(policy) = policy {
+        print_checks(defs, policy, input_opts, formatter)
     } else {
-        match find_bt(defs, input_opts) {
-            Ok(r) => print_results(r, formatter),
+        let languages = if input_opts.languages {
+            Some(langs::construct(defopts.language_defs, None)?)
+        } else {
+            None
+        };
+        match find_bt(defs, languages, input_opts) {
+            Ok(mut r) => {
+                if output_opts.primary {
+                    r.iter_mut().for_each(|bt| bt.retain_primary());
+                }
+                print_results(r, formatter)
+            }
             Err(e) => Err(e),
         }
     }
@@ -147,6 +480,7 @@ fn perform(opts: cli::Options) -> Result<()> {
 fn errors_to_string(e: MeisterError) -> String {
     use MeisterError::*;
     match e {
+        Archive(m) => format!("archive error: {}", m),
         Array(errs) => errs
             .into_iter()
             .map(errors_to_string)
@@ -154,11 +488,18 @@ fn errors_to_string(e: MeisterError) -> String {
             .join("\n"),
         Fatal(m) => format!("fatal: {}", m),
         IO(e) => format!("io error: {}", e),
+        Git(m) => format!("git error: {}", m),
         Json(e) => format!("parse error: {}", e),
         NotImplemented => "not implemented yet.".to_string(),
         NotProject(file) => format!("{}: not project", file),
+        PolicyViolation(count) => format!("{} project(s) violated the policy", count),
         NoProjectSpecified() => "no project specified.".to_string(),
         ProjectNotFound(p) => format!("{}: project not found", p.display()),
+        UnknownTool(name, Some(closest)) => format!(
+            "{}: unknown build tool, did you mean \"{}\"?",
+            name, closest
+        ),
+        UnknownTool(name, None) => format!("{}: unknown build tool", name),
         UnsupportedArchiveFormat(f) => format!("{}: unsupported archive format", f),
         Warning(m) => format!("warning: {}", m),
     }
@@ -200,23 +541,30 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
-
     use super::*;
 
     #[test]
     fn test_error_message() {
         use MeisterError::*;
+        assert_eq!(
+            "archive error: test",
+            errors_to_string(Archive("test".to_string()))
+        );
         assert_eq!("fatal: test", errors_to_string(Fatal("test".to_string())));
         assert_eq!(
             "io error: test",
-            errors_to_string(IO(std::io::Error::new(std::io::ErrorKind::Other, "test",)))
+            errors_to_string(IO(std::io::Error::other("test")))
         );
         assert_eq!(
             "parse error: missing field `test`",
             errors_to_string(Json(serde::de::Error::missing_field("test")))
         );
+        assert_eq!("git error: test", errors_to_string(Git("test".to_string())));
         assert_eq!("not implemented yet.", errors_to_string(NotImplemented));
+        assert_eq!(
+            "2 project(s) violated the policy",
+            errors_to_string(PolicyViolation(2))
+        );
         assert_eq!(
             "no project specified.",
             errors_to_string(NoProjectSpecified())
@@ -229,6 +577,13 @@ mod tests {
             "test: unsupported archive format",
             errors_to_string(UnsupportedArchiveFormat("test".to_string())),
         );
+        assert_eq!(
+            "Gradel: unknown build tool, did you mean \"Gradle\"?",
+            errors_to_string(UnknownTool(
+                "Gradel".to_string(),
+                Some("Gradle".to_string())
+            ))
+        );
         assert_eq!(
             "warning: test",
             errors_to_string(Warning("test".to_string()))
@@ -270,6 +625,317 @@ mod tests {
         assert!(r.is_ok());
     }
 
+    #[test]
+    fn test_success_parallel() {
+        let r = rust_main(
+            ["btmeister", "../testdata", "--threads", "4"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        );
+        assert!(r.is_ok());
+    }
+
+    #[test]
+    fn test_success_archive_roots() {
+        let r = rust_main(
+            [
+                "btmeister",
+                "../testdata/fibonacci.zip",
+                "../testdata/hello.tar.gz",
+                "--archive-roots",
+                "--languages",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        );
+        assert!(r.is_ok());
+    }
+
+    #[test]
+    fn test_success_primary() {
+        let r = rust_main(
+            ["btmeister", "..", "--primary", "--format", "yaml"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        );
+        assert!(r.is_ok());
+    }
+
+    #[test]
+    fn test_success_categories() {
+        let r = rust_main(
+            [
+                "btmeister",
+                "../testdata/fibonacci",
+                "--category",
+                "build",
+                "--exclude-category",
+                "ci",
+                "--format",
+                "csv",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        );
+        assert!(r.is_ok());
+    }
+
+    #[test]
+    fn test_tool_filters() {
+        let r = rust_main(
+            [
+                "btmeister",
+                "../testdata/fibonacci",
+                "--only",
+                "Gradle,Make",
+                "--exclude-tool",
+                "Docker",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        );
+        assert!(r.is_ok());
+
+        let r = rust_main(
+            [
+                "btmeister",
+                "../testdata/fibonacci",
+                "--category",
+                "ci",
+                "--only",
+                "Gradle",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        );
+        assert!(r.is_ok());
+
+        let r = rust_main(
+            ["btmeister", "../testdata/fibonacci", "--only", "Gradel"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        );
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn test_success_languages() {
+        let r = rust_main(
+            [
+                "btmeister",
+                "../testdata/fibonacci",
+                "../testdata/hello.tar.gz",
+                "--languages",
+                "--format",
+                "xml",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        );
+        assert!(r.is_ok());
+    }
+
+    #[test]
+    fn test_history_of_non_repository() {
+        let r = rust_main(
+            ["btmeister", "../testdata/hello", "--history", "--tags-only"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        );
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        use btmeister::diff::Change;
+        let meister = Meister::default();
+        let d = find_diff(&meister, "../testdata/hello", "../testdata/hello.tar.gz").unwrap();
+        assert!(d.is_empty(), "{:?}", d);
+
+        let d = find_diff(
+            &meister,
+            "../testdata/fibonacci",
+            "../testdata/hello.tar.gz",
+        )
+        .unwrap();
+        assert_eq!(vec!["Cargo".to_string()], d.added_tools);
+        assert_eq!(vec!["Gradle".to_string()], d.removed_tools);
+        assert_eq!(
+            vec![
+                Change::Added {
+                    name: "Cargo".to_string(),
+                    path: "Cargo.toml".to_string()
+                },
+                Change::Removed {
+                    name: "Gradle".to_string(),
+                    path: "build.gradle".to_string()
+                },
+            ],
+            d.changes
+        );
+
+        let r = rust_main(
+            [
+                "btmeister",
+                "../testdata/hello",
+                "../testdata/hello.tar.gz",
+                "--diff",
+                "--format",
+                "json",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        );
+        assert!(r.is_ok());
+
+        let r = rust_main(
+            ["btmeister", "../testdata/hello", "--diff"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        );
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let r = rust_main(
+            [
+                "btmeister",
+                "../testdata/fibonacci",
+                "--check",
+                "../testdata/policy.json",
+                "--format",
+                "yaml",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        );
+        assert!(matches!(r, Err(MeisterError::PolicyViolation(1))));
+
+        let r = rust_main(
+            [
+                "btmeister",
+                "../testdata/fibonacci",
+                "--check",
+                "../testdata/append_def.json",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        );
+        assert!(matches!(r, Err(MeisterError::Json(_))));
+
+        let file =
+            std::env::temp_dir().join(format!("btmeister-policy-{}.json", std::process::id()));
+        std::fs::write(&file, r#"{"require-one-of":[["Gradel"]]}"#).unwrap();
+        let r = rust_main(
+            ["btmeister", "../testdata/fibonacci", "--check"]
+                .iter()
+                .map(|s| s.to_string())
+                .chain(std::iter::once(file.display().to_string()))
+                .collect(),
+        );
+        let _ = std::fs::remove_file(&file);
+        assert!(matches!(r, Err(MeisterError::Array(e))
+            if matches!(&e[..], [MeisterError::UnknownTool(n, Some(c))] if n == "Gradel" && c == "Gradle")));
+    }
+
+    #[test]
+    fn test_expect() {
+        let file =
+            std::env::temp_dir().join(format!("btmeister-expect-{}.json", std::process::id()));
+        let args = |extra: &[&str]| {
+            ["btmeister", "../testdata/fibonacci", "--expect"]
+                .iter()
+                .map(|s| s.to_string())
+                .chain(std::iter::once(file.display().to_string()))
+                .chain(extra.iter().map(|s| s.to_string()))
+                .collect::<Vec<String>>()
+        };
+        assert!(rust_main(args(&[])).is_err());
+        assert!(rust_main(args(&["--update-expect"])).is_ok());
+        assert!(rust_main(args(&[])).is_ok());
+
+        // the bases are stored relative to the expectation file.
+        let snapshot = std::fs::read_to_string(&file).unwrap();
+        assert!(!snapshot.contains(r#""base":"../testdata/fibonacci""#));
+        let mut other = args(&[]);
+        other[1] = "../testdata/../testdata/fibonacci".to_string();
+        assert!(rust_main(other).is_ok());
+
+        // the expected but missing projects are reported.
+        let missing = snapshot.replacen(
+            "[",
+            r#"[{"base":"missing","build-tools":[{"path":"Makefile","tool-name":"Make"}]},"#,
+            1,
+        );
+        std::fs::write(&file, missing).unwrap();
+        assert!(rust_main(args(&[])).is_err());
+        std::fs::write(&file, &snapshot).unwrap();
+
+        let drifted = std::fs::read_to_string(&file)
+            .unwrap()
+            .replace("build.gradle", "pom.xml")
+            .replace("Gradle", "Apache Maven");
+        std::fs::write(&file, drifted).unwrap();
+        assert!(rust_main(args(&["--format", "yaml"])).is_err());
+        let _ = std::fs::remove_file(file);
+    }
+
+    #[test]
+    fn test_cache() {
+        let file =
+            std::env::temp_dir().join(format!("btmeister-cli-cache-{}.json", std::process::id()));
+        let args = [
+            "btmeister",
+            "../testdata/fibonacci",
+            "--languages",
+            "--cache",
+            &file.display().to_string(),
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>();
+        assert!(rust_main(args.clone()).is_ok());
+        assert!(file.exists());
+        assert!(rust_main(args).is_ok());
+        let _ = std::fs::remove_file(file);
+    }
+
+    #[test]
+    fn test_watch_archive() {
+        let r = rust_main(
+            ["btmeister", "../testdata/hello.tar.gz", "--watch"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        );
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn test_invalid_excludes() {
+        let r = rust_main(
+            ["btmeister", "../testdata/fibonacci", "--excludes", "re:("]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        );
+        assert!(r.is_err());
+    }
+
     #[test]
     fn test_project_not_found() {
         let r = rust_main(
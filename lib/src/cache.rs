@@ -0,0 +1,590 @@
+/*!
+ * This module provides the on-disk cache for the incremental detection in the directories.
+ * The cache stores the entries of each directory and the build tools detected in them,
+ * keyed by the modification time of the directory.
+ * On the next detection, the directories whose modification times were not changed are not read again,
+ * and their entries and build tools are restored from the cache.
+ *
+ * The whole cache is invalidated when the build tool definitions, the ignore types,
+ * or the detection options ([Meister::with_multi_match], [Meister::with_tasks], [Meister::with_versions],
+ * and [Meister::with_tool_filter]) change.
+ * Also, the subtree of a directory is read again if the `.gitignore`, `.ignore`, or `.git/info/exclude` file
+ * of the directory was modified, and the whole cache is invalidated if the global gitignore file was modified.
+ *
+ * Note that the modification time of a directory changes only when its entries are added, removed, or renamed.
+ * Therefore, the files read for the detection (e.g., `pyproject.toml` for the content patterns,
+ * `Makefile` for the tasks, and `gradle/wrapper/gradle-wrapper.properties` for the versions) are stamped
+ * with their modification times, whether or not a build tool was found in them,
+ * and the directory is read again if one of them was modified, created, or removed.
+ * The archive files in the directories are also stamped with their modification times,
+ * and the build tools in them are restored from the cache without listing their entries again.
+ */
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::hash::{Hash, Hasher};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use crate::extractors::SEPARATOR;
+use crate::{
+    build_result, build_walker, find_build_tool, is_supported_archive_format,
+    normalize_ignore_types, BuildTool, BuildTools, IgnoreType, Matcher, Meister, MeisterError,
+    Result, Source,
+};
+
+/// the ignore files whose modifications invalidate the subtree of the directory.
+const IGNORE_FILES: [&str; 3] = [".gitignore", ".ignore", ".git/info/exclude"];
+
+/// Cache is the content of the cache file.
+#[derive(Serialize, Deserialize, Default)]
+struct Cache {
+    /// the digest of the definitions and the options which produced this cache.
+    key: String,
+    /// the cached directories keyed by their paths.
+    dirs: BTreeMap<PathBuf, CachedDir>,
+    /// the cached archive files keyed by their paths.
+    #[serde(default)]
+    archives: BTreeMap<PathBuf, CachedArchive>,
+}
+
+/// CachedDir represents the entries of a directory in the cache.
+#[derive(Serialize, Deserialize, Clone)]
+struct CachedDir {
+    stamp: Stamp,
+    /// the entries of the directory sorted by their names, excluding the ignored ones.
+    entries: Vec<CachedEntry>,
+}
+
+/// CachedArchive represents the build tools detected in an archive file in the cache.
+#[derive(Serialize, Deserialize, Clone)]
+struct CachedArchive {
+    mtime: Option<(u64, u32)>,
+    /// the build tools with the entry paths in the archive file, including the excluded ones.
+    tools: Vec<(String, CachedTool)>,
+    /// the file entries in the archive file for the language census, recorded only if the census is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    files: Option<Vec<String>>,
+}
+
+/// Stamp represents the modification times of the directory and its ignore files.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+struct Stamp {
+    mtime: Option<(u64, u32)>,
+    ignores: Vec<Option<(u64, u32)>>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct CachedEntry {
+    name: String,
+    is_dir: bool,
+    is_file: bool,
+    tool: Option<CachedTool>,
+    /// the modification times of the files read for the detection, with the paths relative to the base directory.
+    /// The files are recorded even if no build tool was found (e.g., `pyproject.toml` without `[tool.poetry]`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    inputs: Vec<(PathBuf, Option<(u64, u32)>)>,
+}
+
+/// CachedTool represents a detected build tool, referring the definitions by their names.
+#[derive(Serialize, Deserialize, Clone)]
+struct CachedTool {
+    def: String,
+    alternatives: Vec<String>,
+    version: Option<String>,
+    wrapper: bool,
+    tasks: Vec<String>,
+}
+
+/// Walked holds the results of the cached traversal.
+struct Walked {
+    tools: Vec<BuildTool>,
+    errs: Vec<MeisterError>,
+    counts: Option<Vec<usize>>,
+}
+
+impl CachedDir {
+    /// is_fresh returns true if none of the files read for the detection in this directory were modified.
+    fn is_fresh(&self, base: &Path) -> bool {
+        self.entries
+            .iter()
+            .flat_map(|e| e.inputs.iter())
+            .all(|(path, mtime)| mtime_of(&base.join(path)) == *mtime)
+    }
+}
+
+impl Cache {
+    /// load reads the cache file, and returns the empty cache if the file is missing, broken, or built with the other key.
+    fn load(path: &Path, key: &str) -> Cache {
+        let cache = OpenOptions::new()
+            .read(true)
+            .open(path)
+            .ok()
+            .and_then(|f| serde_json::from_reader::<_, Cache>(BufReader::new(f)).ok());
+        match cache {
+            Some(c) if c.key == key => c,
+            Some(_) => {
+                log::info!("{}: cache invalidated", path.display());
+                Cache::new(key)
+            }
+            None => Cache::new(key),
+        }
+    }
+
+    fn new(key: &str) -> Cache {
+        Cache {
+            key: key.to_string(),
+            dirs: BTreeMap::new(),
+            archives: BTreeMap::new(),
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(MeisterError::IO)?;
+        }
+        match OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+        {
+            Ok(f) => serde_json::to_writer(BufWriter::new(f), self).map_err(MeisterError::Json),
+            Err(e) => Err(MeisterError::IO(e)),
+        }
+    }
+}
+
+impl Meister {
+    /// with_cache enables the on-disk cache for the detection in the directories, stored in the given file.
+    /// With the cache, [Meister::find] reads only the directories modified after the previous detection.
+    /// The directories are traversed in the current thread, regardless of [Meister::with_threads].
+    ///
+    /// The cache is invalidated when the definitions, the ignore types, the global gitignore file, or the detection options change,
+    /// and the subtree is read again when its `.gitignore`, `.ignore`, or `.git/info/exclude` file was modified.
+    /// A directory is also read again when the files read for the detection in it (the build files,
+    /// and the files beside them for the versions and the wrappers) were modified,
+    /// and an archive file is listed again only when it was modified.
+    pub fn with_cache(mut self, path: PathBuf) -> Self {
+        self.cache = Some(path);
+        self
+    }
+
+    /// find_directory_cached detects the build tools in the given directory with the cache file.
+    pub(crate) fn find_directory_cached(&self, base: PathBuf, path: &Path) -> Result<BuildTools> {
+        let key = self.cache_key();
+        let old = Cache::load(path, &key);
+        let mut new = Cache::new(&key);
+        let mut walked = Walked {
+            tools: vec![],
+            errs: vec![],
+            counts: self.census.as_ref().map(|c| c.counter()),
+        };
+        self.visit(&base, Path::new(""), &old, &mut new, false, &mut walked);
+        for (dir, cached) in old.dirs.into_iter() {
+            if !dir.starts_with(&base) {
+                new.dirs.entry(dir).or_insert(cached);
+            }
+        }
+        for (archive, cached) in old.archives.into_iter() {
+            if !archive.starts_with(&base) {
+                new.archives.entry(archive).or_insert(cached);
+            }
+        }
+        if let Err(e) = new.save(path) {
+            log::warn!("{}: saving cache: {:?}", path.display(), e);
+        }
+        let languages = match (&self.census, &walked.counts) {
+            (Some(census), Some(counts)) => census.result(counts),
+            _ => vec![],
+        };
+        build_result(base, walked.tools, walked.errs, languages)
+    }
+
+    fn visit(
+        &self,
+        base: &Path,
+        rel: &Path,
+        old: &Cache,
+        new: &mut Cache,
+        force: bool,
+        walked: &mut Walked,
+    ) {
+        let dir = base.join(rel);
+        let stamp = stamp_of(&dir);
+        let (entries, force) = match old.dirs.get(&dir) {
+            Some(cached) if !force && cached.stamp == stamp && cached.is_fresh(base) => {
+                (cached.entries.clone(), false)
+            }
+            cached => {
+                let force = force || cached.is_some_and(|c| c.stamp.ignores != stamp.ignores);
+                log::debug!("{}: reading directory", dir.display());
+                match self.read_dir(base, rel) {
+                    Ok(entries) => (entries, force),
+                    Err(e) => {
+                        walked.errs.push(e);
+                        return;
+                    }
+                }
+            }
+        };
+        new.dirs.insert(
+            dir,
+            CachedDir {
+                stamp,
+                entries: entries.clone(),
+            },
+        );
+        for entry in entries {
+            let target = rel.join(&entry.name);
+            if self.excludes.matches(&target) {
+                continue;
+            }
+            if let (Some(census), Some(counts), true) =
+                (&self.census, walked.counts.as_mut(), entry.is_file)
+            {
+                census.count(&target, counts);
+            }
+            if let Some(tool) = &entry.tool {
+                if let Some(bt) = self.restore(base.join(&target), tool) {
+                    walked.tools.push(bt);
+                }
+            }
+            if self.open_archives && entry.is_file && is_supported_archive_format(&target) {
+                self.visit_archive(&base.join(&target), old, new, walked);
+            }
+            if entry.is_dir {
+                self.visit(base, &target, old, new, force, walked);
+            }
+        }
+    }
+
+    /// visit_archive detects the build tools in the archive file found in the directory, like [Meister::find_in_archive].
+    /// The archive files whose modification times were not changed are not listed again.
+    fn visit_archive(&self, archive: &Path, old: &Cache, new: &mut Cache, walked: &mut Walked) {
+        let mtime = mtime_of(archive);
+        let cached = match old.archives.get(archive) {
+            Some(cached)
+                if cached.mtime == mtime && (self.census.is_none() || cached.files.is_some()) =>
+            {
+                cached.clone()
+            }
+            _ => {
+                log::debug!("{}: listing archive", archive.display());
+                match self.read_archive(archive, mtime) {
+                    Ok(cached) => cached,
+                    Err(e) => {
+                        log::warn!("{}: listing entries: {:?}", archive.display(), e);
+                        return;
+                    }
+                }
+            }
+        };
+        for (entry, tool) in &cached.tools {
+            if self.excludes.matches(Path::new(entry)) {
+                continue;
+            }
+            let path = format!("{}{}{}", archive.display(), SEPARATOR, entry);
+            if let Some(bt) = self.restore(PathBuf::from(path), tool) {
+                walked.tools.push(bt);
+            }
+        }
+        if let (Some(census), Some(counts), Some(files)) =
+            (&self.census, walked.counts.as_mut(), &cached.files)
+        {
+            for file in files {
+                let path = Path::new(file);
+                if !self.excludes.matches(path) {
+                    census.count(path, counts);
+                }
+            }
+        }
+        new.archives.insert(archive.to_path_buf(), cached);
+    }
+
+    /// read_archive lists the entries of the archive file, and detects the build tools in them.
+    fn read_archive(&self, archive: &Path, mtime: Option<(u64, u32)>) -> Result<CachedArchive> {
+        let listing = self.list_archive(archive)?;
+        let source = Source::Archive(&listing.contents);
+        let tools = listing
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                find_build_tool(self, Path::new(entry), &source)
+                    .map(|bt| (entry.clone(), cached_tool(bt)))
+            })
+            .collect();
+        let files = self.census.as_ref().map(|_| {
+            listing
+                .entries
+                .iter()
+                .filter(|entry| !entry.ends_with('/'))
+                .cloned()
+                .collect()
+        });
+        Ok(CachedArchive {
+            mtime,
+            tools,
+            files,
+        })
+    }
+
+    /// read_dir lists the entries of the directory respecting the ignore types, and detects the build tools in them.
+    fn read_dir(&self, base: &Path, rel: &Path) -> Result<Vec<CachedEntry>> {
+        let mut entries = vec![];
+        let walker = build_walker(base.join(rel), &self.its)
+            .max_depth(Some(1))
+            .build();
+        for entry in walker {
+            let entry = entry.map_err(|e| MeisterError::Warning(format!("walking: {}", e)))?;
+            if entry.depth() == 0 {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            let file_type = entry.file_type();
+            let inputs = RefCell::new(vec![]);
+            let tool = find_build_tool(self, &rel.join(&name), &Source::Recorded(base, &inputs))
+                .map(cached_tool);
+            let mut inputs = inputs.take();
+            inputs.sort();
+            inputs.dedup();
+            entries.push(CachedEntry {
+                name,
+                is_dir: file_type.is_some_and(|t| t.is_dir()),
+                is_file: file_type.is_some_and(|t| t.is_file()),
+                tool,
+                inputs: inputs
+                    .into_iter()
+                    .map(|p| {
+                        let mtime = mtime_of(&base.join(&p));
+                        (p, mtime)
+                    })
+                    .collect(),
+            });
+        }
+        Ok(entries)
+    }
+
+    fn restore(&self, path: PathBuf, tool: &CachedTool) -> Option<BuildTool> {
+        let find = |name: &str| self.defs.iter().find(|d| d.name == name).cloned();
+        let mut bt = BuildTool::new(path, find(&tool.def)?);
+        bt.alternatives = tool.alternatives.iter().filter_map(|n| find(n)).collect();
+        bt.version = tool.version.clone();
+        bt.wrapper = tool.wrapper;
+        bt.tasks = tool.tasks.clone();
+        Some(bt)
+    }
+
+    /// cache_key returns the digest of the definitions and the options affecting the cached entries.
+    fn cache_key(&self) -> String {
+        let mut its = normalize_ignore_types(&self.its);
+        its.sort();
+        let mut hasher = DefaultHasher::new();
+        env!("CARGO_PKG_VERSION").hash(&mut hasher);
+        serde_json::to_string(&self.defs)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        its.hash(&mut hasher);
+        if its.contains(&IgnoreType::GitGlobal) {
+            if let Some(global) = ignore::gitignore::gitconfig_excludes_path() {
+                mtime_of(&global).hash(&mut hasher);
+                global.hash(&mut hasher);
+            }
+        }
+        self.enabled.hash(&mut hasher);
+        self.multi_match.hash(&mut hasher);
+        self.tasks.hash(&mut hasher);
+        self.versions.hash(&mut hasher);
+        self.archive_depth.hash(&mut hasher);
+        self.archive_limits.hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+}
+
+fn cached_tool(bt: BuildTool) -> CachedTool {
+    CachedTool {
+        def: bt.def.name,
+        alternatives: bt.alternatives.into_iter().map(|d| d.name).collect(),
+        version: bt.version,
+        wrapper: bt.wrapper,
+        tasks: bt.tasks,
+    }
+}
+
+fn stamp_of(dir: &Path) -> Stamp {
+    Stamp {
+        mtime: mtime_of(dir),
+        ignores: IGNORE_FILES
+            .iter()
+            .map(|f| mtime_of(&dir.join(f)))
+            .collect(),
+    }
+}
+
+fn mtime_of(path: &Path) -> Option<(u64, u32)> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let d = modified.duration_since(UNIX_EPOCH).ok()?;
+    Some((d.as_secs(), d.subsec_nanos()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defs::BuildToolDefs;
+    use crate::IgnoreType;
+
+    fn names(r: &BuildTools) -> Vec<(String, String)> {
+        (0..r.tools.len())
+            .map(|i| (r.path_of(i).unwrap(), r.tools[i].def.name.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_cache() {
+        let dir = std::env::temp_dir().join(format!("btmeister-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let project = dir.join("project");
+        std::fs::create_dir_all(project.join("sub")).unwrap();
+        std::fs::write(project.join("Makefile"), "all:\n").unwrap();
+        std::fs::write(project.join("sub/build.gradle"), "").unwrap();
+        let file = dir.join("cache.json");
+
+        let meister = Meister::default().with_cache(file.clone());
+        let r = meister.find(project.clone()).unwrap();
+        let expected = names(&Meister::default().find(project.clone()).unwrap());
+        assert_eq!(expected, names(&r));
+        assert!(file.exists());
+
+        // the unchanged directories are restored from the cache.
+        let tampered = std::fs::read_to_string(&file)
+            .unwrap()
+            .replace(r#""def":"Make""#, r#""def":"just""#);
+        std::fs::write(&file, tampered).unwrap();
+        let r = meister.find(project.clone()).unwrap();
+        assert!(r.tools.iter().any(|bt| bt.def.name == "just"));
+
+        // the modified directories are read again.
+        std::fs::create_dir_all(project.join("sub2")).unwrap();
+        std::fs::write(project.join("sub2/pom.xml"), "").unwrap();
+        let r = meister.find(project.clone()).unwrap();
+        assert!(r.tools.iter().any(|bt| bt.def.name == "Apache Maven"));
+        assert!(r.tools.iter().any(|bt| bt.def.name == "Make"));
+
+        // the cache is invalidated by the ignore types.
+        std::fs::write(
+            &file,
+            std::fs::read_to_string(&file)
+                .unwrap()
+                .replace(r#""def":"Make""#, r#""def":"just""#),
+        )
+        .unwrap();
+        let hidden = Meister::new(BuildToolDefs::default(), vec![IgnoreType::Hidden])
+            .unwrap()
+            .with_cache(file.clone());
+        let r = hidden.find(project.clone()).unwrap();
+        assert!(!r.tools.iter().any(|bt| bt.def.name == "just"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_cache_with_modified_files() {
+        let dir = std::env::temp_dir().join(format!("btmeister-inputs-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let project = dir.join("project");
+        std::fs::create_dir_all(project.join("gradle/wrapper")).unwrap();
+        std::fs::write(project.join("Makefile"), "all:\n").unwrap();
+        std::fs::write(project.join("build.gradle"), "").unwrap();
+        let properties = project.join("gradle/wrapper/gradle-wrapper.properties");
+        let url = "distributionUrl=https\\://services.gradle.org/distributions/gradle-";
+        std::fs::write(&properties, format!("{}7.4.2-bin.zip\n", url)).unwrap();
+        let file = dir.join("cache.json");
+        let meister = Meister::default()
+            .with_tasks(true)
+            .with_versions(true)
+            .with_cache(file.clone());
+        let tool = |r: &BuildTools, name: &str| {
+            r.tools
+                .iter()
+                .find(|bt| bt.def.name == name)
+                .cloned()
+                .unwrap()
+        };
+        let r = meister.find(project.clone()).unwrap();
+        assert_eq!(vec!["all"], tool(&r, "Make").tasks);
+        assert_eq!(Some("7.4.2".to_string()), tool(&r, "Gradle").version);
+
+        // the edits of the files in place do not change the modification times of the directories.
+        std::fs::write(project.join("Makefile"), "all:\ntest:\n").unwrap();
+        std::fs::write(&properties, format!("{}8.5-bin.zip\n", url)).unwrap();
+        touch(&project.join("Makefile"));
+        touch(&properties);
+        let r = meister.find(project.clone()).unwrap();
+        assert_eq!(vec!["all", "test"], tool(&r, "Make").tasks);
+        assert_eq!(Some("8.5".to_string()), tool(&r, "Gradle").version);
+
+        // the removals of the files read in the other directories are also noticed.
+        std::fs::remove_file(&properties).unwrap();
+        let r = meister.find(project.clone()).unwrap();
+        assert_eq!(None, tool(&r, "Gradle").version);
+
+        // the files read without detecting any build tool are also noticed.
+        let pyproject = project.join("pyproject.toml");
+        std::fs::write(&pyproject, "[project]\n").unwrap();
+        let r = meister.find(project.clone()).unwrap();
+        assert!(!r.tools.iter().any(|bt| bt.def.name == "Poetry"));
+        std::fs::write(&pyproject, "[tool.poetry]\n").unwrap();
+        touch(&pyproject);
+        let r = meister.find(project.clone()).unwrap();
+        assert!(r.tools.iter().any(|bt| bt.def.name == "Poetry"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_cache_with_archives() {
+        let dir = std::env::temp_dir().join(format!("btmeister-archives-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let project = dir.join("project");
+        std::fs::create_dir_all(&project).unwrap();
+        let archive = project.join("hello.tar.gz");
+        std::fs::copy("../testdata/hello.tar.gz", &archive).unwrap();
+        let file = dir.join("cache.json");
+        let meister = Meister::default()
+            .with_open_archives(true)
+            .with_cache(file.clone());
+        let r = meister.find(project.clone()).unwrap();
+        assert_eq!(
+            vec![(
+                "hello.tar.gz!/hello/Cargo.toml".to_string(),
+                "Cargo".to_string()
+            )],
+            names(&r)
+        );
+
+        // the unchanged archive files are restored from the cache.
+        let tampered = std::fs::read_to_string(&file)
+            .unwrap()
+            .replace(r#""def":"Cargo""#, r#""def":"just""#);
+        std::fs::write(&file, tampered).unwrap();
+        let r = meister.find(project.clone()).unwrap();
+        assert!(r.tools.iter().any(|bt| bt.def.name == "just"));
+
+        // the modified archive files are listed again.
+        touch(&archive);
+        let r = meister.find(project.clone()).unwrap();
+        assert!(r.tools.iter().any(|bt| bt.def.name == "Cargo"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    /// touch sets the modification time of the given file apart from the previous one,
+    /// for the file systems with the coarse timestamps.
+    fn touch(path: &Path) {
+        let file = OpenOptions::new().write(true).open(path).unwrap();
+        let mtime = file.metadata().unwrap().modified().unwrap();
+        file.set_modified(mtime + std::time::Duration::from_secs(10))
+            .unwrap();
+    }
+}
@@ -19,12 +19,34 @@
  *   }
  * ]
  * ```
+ *
+ * The optional `contents` field requires the matched build file to contain one of the given patterns.
+ * The pattern is a literal string, or a regular expression when it starts with `re:`.
+ * The contents are read only after the file name matched the `build-files`.
+ * The definitions whose contents matched precede the definitions without `contents` for the same file,
+ * e.g., the `Makefile` generated by automake is detected as automake, not Make.
+ *
+ * ```json
+ * [
+ *   {
+ *     "name": "Poetry",
+ *     "build-files": ["pyproject.toml"],
+ *     "contents": ["[tool.poetry]"],
+ *     "url": "https://python-poetry.org/"
+ *   }
+ * ]
+ * ```
+ *
+ * The optional `categories` field classifies the build tool (`build`, `task-runner`, `ci`, `container`, `iac`, `docs`, and `package-manager`).
+ * The first category is treated as the main kind of the build tool.
  */
 use core::panic;
+use std::fmt::Display;
 use std::fs::OpenOptions;
 use std::io::BufReader;
 use std::path::PathBuf;
 
+use clap::ValueEnum;
 use rust_embed::RustEmbed;
 use serde::{Deserialize, Serialize};
 
@@ -32,7 +54,35 @@ use crate::{MeisterError, Result};
 
 #[derive(RustEmbed)]
 #[folder = "../assets"]
-struct Asset;
+pub(crate) struct Asset;
+
+/// Category represents the kind of the build tool.
+#[derive(Serialize, Deserialize, ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Category {
+    Build,
+    TaskRunner,
+    Ci,
+    Container,
+    Iac,
+    Docs,
+    PackageManager,
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Category::Build => "build",
+            Category::TaskRunner => "task-runner",
+            Category::Ci => "ci",
+            Category::Container => "container",
+            Category::Iac => "iac",
+            Category::Docs => "docs",
+            Category::PackageManager => "package-manager",
+        };
+        write!(f, "{}", name)
+    }
+}
 
 /// BuildToolDef represents a build tool definition.
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -40,6 +90,13 @@ pub struct BuildToolDef {
     pub name: String,
     #[serde(rename = "build-files")]
     pub build_files: Vec<String>,
+    /// the patterns one of which must appear in the build file.
+    /// The pattern starting with `re:` is treated as a regular expression, otherwise a literal string.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contents: Vec<String>,
+    /// the categories of the build tool. The first one is the main category.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<Category>,
     pub url: String,
     // #[serde(skip)]
     // matchers: Vec<Box<dyn Matcher>>,
@@ -121,6 +178,16 @@ impl BuildToolDefs {
     pub fn append(&mut self, other: &mut BuildToolDefs) {
         self.defs.append(&mut other.defs);
     }
+
+    /// retain_categories keeps only the definitions belonging to one of the `includes` categories
+    /// and none of the `excludes` categories.
+    /// Empty `includes` keeps all of the definitions not excluded.
+    pub fn retain_categories(&mut self, includes: &[Category], excludes: &[Category]) {
+        self.defs.retain(|def| {
+            (includes.is_empty() || def.categories.iter().any(|c| includes.contains(c)))
+                && !def.categories.iter().any(|c| excludes.contains(c))
+        });
+    }
 }
 
 impl BuildToolDef {
@@ -129,9 +196,29 @@ impl BuildToolDef {
         BuildToolDef {
             name,
             build_files,
+            contents: vec![],
+            categories: vec![],
             url,
         }
     }
+
+    /// with_contents sets the content patterns which must appear in the build file.
+    pub fn with_contents(mut self, contents: Vec<String>) -> Self {
+        self.contents = contents;
+        self
+    }
+
+    /// with_categories sets the categories of the build tool.
+    pub fn with_categories(mut self, categories: Vec<Category>) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    /// main_category returns the first category of the build tool.
+    /// The definition without categories is treated as a build tool ([Category::Build]).
+    pub fn main_category(&self) -> Category {
+        self.categories.first().copied().unwrap_or(Category::Build)
+    }
 }
 
 /// The `construct` function creates a BuildToolDefs object from the given definition file and append file.
@@ -183,7 +270,7 @@ mod test {
     #[test]
     fn test_parse() {
         let defs = BuildToolDefs::default();
-        assert_eq!(46, defs.len())
+        assert_eq!(48, defs.len())
     }
 
     #[test]
@@ -201,7 +288,7 @@ mod test {
         let r = construct(None, None);
         assert!(r.is_ok());
         if let Ok(result) = r {
-            assert_eq!(46, result.len());
+            assert_eq!(48, result.len());
             assert!(!result.is_empty());
         }
     }
@@ -211,7 +298,7 @@ mod test {
         let r = construct(Some(PathBuf::from("../assets/buildtools.json")), None);
         assert!(r.is_ok());
         if let Ok(result) = r {
-            assert_eq!(46, result.len());
+            assert_eq!(48, result.len());
             assert!(!result.is_empty());
         }
     }
@@ -221,11 +308,41 @@ mod test {
         let r = construct(None, Some(PathBuf::from("../testdata/append_def.json")));
         assert!(r.is_ok());
         if let Ok(result) = r {
-            assert_eq!(48, result.len());
+            assert_eq!(50, result.len());
             assert!(!result.is_empty());
         }
     }
 
+    #[test]
+    fn test_contents_field() {
+        let defs = BuildToolDefs::default();
+        let poetry = defs.iter().find(|d| d.name == "Poetry");
+        assert!(poetry.is_some());
+        if let Some(def) = poetry {
+            assert_eq!(vec!["[tool.poetry]".to_string()], def.contents);
+        }
+        let make = defs.iter().find(|d| d.name == "Make");
+        assert!(make.is_some_and(|d| d.contents.is_empty()));
+    }
+
+    #[test]
+    fn test_categories() {
+        let defs = BuildToolDefs::default();
+        assert!(defs.iter().all(|d| !d.categories.is_empty()));
+        let make = defs.iter().find(|d| d.name == "Make");
+        assert!(make.is_some_and(|d| d.categories == vec![Category::TaskRunner, Category::Build]));
+
+        let mut ci = BuildToolDefs::default();
+        ci.retain_categories(&[Category::Ci], &[]);
+        assert_eq!(5, ci.len());
+
+        let mut no_ci = BuildToolDefs::default();
+        no_ci.retain_categories(&[], &[Category::Ci, Category::Container]);
+        assert!(no_ci.iter().all(|d| !d.categories.contains(&Category::Ci)
+            && !d.categories.contains(&Category::Container)));
+        assert!(no_ci.iter().any(|d| d.name == "Gradle"));
+    }
+
     #[test]
     fn test_new_and_extend() {
         let mut defs1 = BuildToolDefs::new(Vec::<BuildToolDef>::new());
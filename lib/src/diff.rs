@@ -0,0 +1,263 @@
+/*!
+ * This module compares two results of the detection, and reports the added, removed, and moved build files.
+ * The results are compared by the relative paths from the project bases ([BuildTools::path_of]),
+ * therefore, the projects in the different locations (e.g., a directory and an archive file) are comparable.
+ * Note that [diff] does not remove the top-level directory wrapping the project in the archive file
+ * (e.g., `fibonacci/build.gradle` in `fibonacci.zip`).
+ * Compare the results of [Meister::find_projects](crate::Meister::find_projects) to remove it.
+ *
+ * The removed file and the added file of the same build tool with the same file name are reported as a moved file.
+ *
+ * Also, the saved results in the JSON format of `btmeister --format json` are loaded by [load].
+ */
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::defs::BuildToolDef;
+use crate::{BuildTool, BuildTools, MeisterError, Result};
+
+/// Diff represents the differences between two results of the detection.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diff {
+    /// the base of the older result.
+    pub before: PathBuf,
+    /// the base of the newer result.
+    pub after: PathBuf,
+    /// the names of the build tools appearing only in the newer result.
+    pub added_tools: Vec<String>,
+    /// the names of the build tools appearing only in the older result.
+    pub removed_tools: Vec<String>,
+    /// the changes of the build files in the order of their paths.
+    pub changes: Vec<Change>,
+}
+
+/// Change represents a change of a build file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// the build file only in the newer result.
+    Added { name: String, path: String },
+    /// the build file only in the older result.
+    Removed { name: String, path: String },
+    /// the build file moved from `from` to `to`.
+    Moved {
+        name: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl Change {
+    /// name returns the name of the build tool of this change.
+    pub fn name(&self) -> &str {
+        match self {
+            Change::Added { name, .. }
+            | Change::Removed { name, .. }
+            | Change::Moved { name, .. } => name,
+        }
+    }
+
+    fn path(&self) -> &str {
+        match self {
+            Change::Added { path, .. } | Change::Removed { path, .. } => path,
+            Change::Moved { from, .. } => from,
+        }
+    }
+}
+
+impl Diff {
+    /// is_empty returns true if no differences were found.
+    pub fn is_empty(&self) -> bool {
+        self.added_tools.is_empty() && self.removed_tools.is_empty() && self.changes.is_empty()
+    }
+}
+
+/// diff compares the given two results, and returns the differences from `before` to `after`.
+pub fn diff(before: &BuildTools, after: &BuildTools) -> Diff {
+    let olds = entries(before);
+    let news = entries(after);
+    let mut removed = olds
+        .iter()
+        .filter(|e| !news.contains(e))
+        .cloned()
+        .collect::<Vec<(String, String)>>();
+    let mut added = news
+        .iter()
+        .filter(|e| !olds.contains(e))
+        .cloned()
+        .collect::<Vec<(String, String)>>();
+    let mut changes = vec![];
+    removed.retain(|(from, name)| {
+        match added
+            .iter()
+            .position(|(to, n)| n == name && file_name(to) == file_name(from))
+        {
+            Some(index) => {
+                let (to, _) = added.remove(index);
+                changes.push(Change::Moved {
+                    name: name.clone(),
+                    from: from.clone(),
+                    to,
+                });
+                false
+            }
+            None => true,
+        }
+    });
+    changes.extend(
+        removed
+            .into_iter()
+            .map(|(path, name)| Change::Removed { name, path }),
+    );
+    changes.extend(
+        added
+            .into_iter()
+            .map(|(path, name)| Change::Added { name, path }),
+    );
+    changes.sort_by(|a, b| a.path().cmp(b.path()));
+
+    let old_names = names(before);
+    let new_names = names(after);
+    Diff {
+        before: before.base.clone(),
+        after: after.base.clone(),
+        added_tools: new_names.difference(&old_names).cloned().collect(),
+        removed_tools: old_names.difference(&new_names).cloned().collect(),
+        changes,
+    }
+}
+
+/// load reads the saved results in the JSON format of `btmeister --format json`.
+/// The build tool definitions of the loaded results have only their names.
+pub fn load(path: &Path) -> Result<Vec<BuildTools>> {
+    let value: Value = match File::open(path) {
+        Ok(file) => match serde_json::from_reader(BufReader::new(file)) {
+            Ok(v) => v,
+            Err(e) => return Err(MeisterError::Json(e)),
+        },
+        Err(e) => return Err(MeisterError::IO(e)),
+    };
+    let invalid = || MeisterError::Fatal(format!("{}: not a result of btmeister", path.display()));
+    let mut result = vec![];
+    for project in value.as_array().ok_or_else(invalid)? {
+        let base = PathBuf::from(project["base"].as_str().ok_or_else(invalid)?);
+        let mut tools = vec![];
+        for tool in project["build-tools"].as_array().ok_or_else(invalid)? {
+            let (Some(p), Some(name)) = (tool["path"].as_str(), tool["tool-name"].as_str()) else {
+                return Err(invalid());
+            };
+            let def = BuildToolDef::new(name.to_string(), vec![], "".to_string());
+            tools.push(BuildTool::new(base.join(p), def));
+        }
+        result.push(BuildTools::new(base, tools));
+    }
+    Ok(result)
+}
+
+fn entries(tools: &BuildTools) -> Vec<(String, String)> {
+    tools
+        .tools
+        .iter()
+        .enumerate()
+        .map(|(index, bt)| {
+            let path = tools
+                .path_of(index)
+                .unwrap_or_else(|_| bt.path.display().to_string());
+            (path, bt.def.name.clone())
+        })
+        .collect()
+}
+
+fn names(tools: &BuildTools) -> BTreeSet<String> {
+    tools.tools.iter().map(|bt| bt.def.name.clone()).collect()
+}
+
+fn file_name(path: &str) -> Option<&std::ffi::OsStr> {
+    Path::new(path).file_name()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tools(base: &str, files: &[(&str, &str)]) -> BuildTools {
+        BuildTools::new(
+            PathBuf::from(base),
+            files
+                .iter()
+                .map(|(path, name)| {
+                    BuildTool::new(
+                        PathBuf::from(base).join(path),
+                        BuildToolDef::new(name.to_string(), vec![], "".to_string()),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = tools(
+            "v1",
+            &[
+                (".travis.yml", "Travis"),
+                ("Makefile", "Make"),
+                ("pom.xml", "Apache Maven"),
+            ],
+        );
+        let after = tools(
+            "v2",
+            &[
+                (".github/workflows/build.yml", "GitHub Actions"),
+                ("pom.xml", "Apache Maven"),
+                ("tools/Makefile", "Make"),
+            ],
+        );
+        let d = diff(&before, &after);
+        assert_eq!(vec!["GitHub Actions".to_string()], d.added_tools);
+        assert_eq!(vec!["Travis".to_string()], d.removed_tools);
+        assert_eq!(
+            vec![
+                Change::Added {
+                    name: "GitHub Actions".to_string(),
+                    path: ".github/workflows/build.yml".to_string()
+                },
+                Change::Removed {
+                    name: "Travis".to_string(),
+                    path: ".travis.yml".to_string()
+                },
+                Change::Moved {
+                    name: "Make".to_string(),
+                    from: "Makefile".to_string(),
+                    to: "tools/Makefile".to_string()
+                },
+            ],
+            d.changes
+        );
+        assert!(diff(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = std::env::temp_dir().join(format!("btmeister-load-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("result.json");
+        std::fs::write(
+            &file,
+            r#"[{"base":"proj","build-tools":[{"path":"build.gradle","tool-name":"Gradle","confidence":1.00}]}]"#,
+        )
+        .unwrap();
+        let r = load(&file).unwrap();
+        assert_eq!(1, r.len());
+        assert_eq!(PathBuf::from("proj"), r[0].base);
+        assert_eq!("build.gradle", r[0].path_of(0).unwrap());
+        assert_eq!("Gradle", r[0].tools[0].def.name);
+
+        std::fs::write(&file, r#"{"base":"proj"}"#).unwrap();
+        assert!(load(&file).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,11 +1,167 @@
+mod ar;
 mod tar;
 mod zip;
 
 use crate::{MeisterError, Result};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{Cursor, Read};
+use std::path::Path;
 use std::{ffi::OsStr, path::PathBuf};
 
+/// the separator between the path of a nested archive and the entry in it,
+/// e.g., `lib/app.jar!/pom.xml` is `pom.xml` in `lib/app.jar`.
+pub const SEPARATOR: &str = "!/";
+
+/// ArchiveLimits represents the limits for reading the archive files, since the archive files may be untrusted.
+/// The limits are shared by an archive file and the archives nested in it.
+/// The archive files exceeding the limits are reported as [MeisterError::Archive].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ArchiveLimits {
+    /// the maximum number of the entries in an archive file, including the entries of the nested archives.
+    pub max_entries: usize,
+    /// the maximum number of the decompressed bytes read in total from an archive file,
+    /// including the bytes of the nested archives.
+    pub max_bytes: u64,
+    /// the maximum length of the entry paths in bytes.
+    pub max_path_length: usize,
+}
+
+impl Default for ArchiveLimits {
+    /// default returns the limits of 100,000 entries, 1 GiB, and 4,096 bytes of the path.
+    fn default() -> Self {
+        ArchiveLimits {
+            max_entries: 100_000,
+            max_bytes: 1 << 30,
+            max_path_length: 4096,
+        }
+    }
+}
+
+/// Budget counts the entries and the bytes read from a top-level archive file and the archives nested in it,
+/// against [ArchiveLimits].
+struct Budget<'a> {
+    limits: &'a ArchiveLimits,
+    entries: Cell<usize>,
+    bytes: Cell<u64>,
+    exceeded: Cell<bool>,
+}
+
+impl<'a> Budget<'a> {
+    fn new(limits: &'a ArchiveLimits) -> Self {
+        Budget {
+            limits,
+            entries: Cell::new(0),
+            bytes: Cell::new(0),
+            exceeded: Cell::new(false),
+        }
+    }
+
+    /// charge counts the given number of the bytes read, and fails if the total exceeds [ArchiveLimits::max_bytes].
+    fn charge(&self, bytes: u64) -> std::io::Result<()> {
+        self.bytes.set(self.bytes.get().saturating_add(bytes));
+        if self.bytes.get() > self.limits.max_bytes {
+            self.exceeded.set(true);
+            Err(std::io::Error::other(format!(
+                "the decompressed data exceeds {} bytes",
+                self.limits.max_bytes
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// accept_entry counts the entry with the given name, and returns `Ok(true)` if the entry should be listed.
+    /// This function returns the error if the entry exceeds the limits,
+    /// and `Ok(false)` with the warning if the entry has an unsafe path.
+    fn accept_entry(&self, name: &str) -> Result<bool> {
+        self.entries.set(self.entries.get() + 1);
+        if self.entries.get() > self.limits.max_entries {
+            self.exceeded.set(true);
+            Err(MeisterError::Archive(format!(
+                "more than {} entries",
+                self.limits.max_entries
+            )))
+        } else if name.len() > self.limits.max_path_length {
+            self.exceeded.set(true);
+            Err(MeisterError::Archive(format!(
+                "the entry path exceeds {} bytes: {}...",
+                self.limits.max_path_length,
+                name.chars().take(64).collect::<String>()
+            )))
+        } else if is_unsafe_path(name) {
+            log::warn!("{}: unsafe entry path, skipped", name);
+            Ok(false)
+        } else {
+            Ok(true)
+        }
+    }
+
+    /// reserve_entries fails if the given number of the entries to be listed exceeds [ArchiveLimits::max_entries]
+    /// together with the entries already counted, for the archives knowing their entries in advance (e.g., zip).
+    /// The entries are counted by [Budget::accept_entry] when they are listed.
+    fn reserve_entries(&self, count: usize) -> Result<()> {
+        if self.entries.get().saturating_add(count) > self.limits.max_entries {
+            self.exceeded.set(true);
+            Err(MeisterError::Archive(format!(
+                "more than {} entries",
+                self.limits.max_entries
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// is_exceeded returns true if one of the limits was exceeded.
+    fn is_exceeded(&self) -> bool {
+        self.exceeded.get()
+    }
+}
+
+/// Input is the archive to read, the file or the bytes in the memory (e.g., the archive nested in another archive).
+#[derive(Clone, Copy)]
+enum Input<'a> {
+    File(&'a Path),
+    Bytes(&'a [u8]),
+}
+
+impl<'a> Input<'a> {
+    fn open(self) -> Result<Box<dyn Read + 'a>> {
+        match self {
+            Input::File(path) => match File::open(path) {
+                Ok(f) => Ok(Box::new(f)),
+                Err(e) => Err(MeisterError::IO(e)),
+            },
+            Input::Bytes(data) => Ok(Box::new(Cursor::new(data))),
+        }
+    }
+}
+
+/// Visitor receives the name of each entry in the archive, and the function reading the content of the entry.
+/// The content is read only if the visitor calls the function.
+type Visitor<'v> = dyn FnMut(&str, &mut dyn FnMut() -> Result<Vec<u8>>) -> Result<()> + 'v;
+
 trait Extractor {
-    fn list_entries(&self, archive_file: PathBuf) -> Result<Vec<String>>;
+    /// visit_entries calls the visitor for each entry of the archive in the order of the archive.
+    /// The entries exceeding the limits stop the visit with the error, and the unsafe entries are skipped.
+    fn visit_entries(&self, input: Input, budget: &Budget, visitor: &mut Visitor) -> Result<()>;
+
+    /// list_entries returns the entries of the archive file.
+    #[cfg(test)]
+    fn list_entries(&self, archive_file: PathBuf, limits: &ArchiveLimits) -> Result<Vec<String>> {
+        let mut result = vec![];
+        self.visit_entries(
+            Input::File(&archive_file),
+            &Budget::new(limits),
+            &mut |name, _| {
+                result.push(name.to_string());
+                Ok(())
+            },
+        )?;
+        Ok(result)
+    }
 
     #[cfg(test)]
     fn format(&self) -> Format;
@@ -19,19 +175,126 @@ pub enum Format {
     TarXz,
     TarZstd,
     Zip,
+    Ar,
+    Deb,
+}
+
+/// Listing is the entries of an archive file, and the contents of the entries selected while listing them.
+#[derive(Debug, Default)]
+pub(crate) struct Listing {
+    pub(crate) entries: Vec<String>,
+    /// the contents of the selected entries keyed by the entry names.
+    pub(crate) contents: HashMap<String, Vec<u8>>,
+}
+
+/// list_nested_entries returns the entries of the archive file, without reading the contents of them.
+#[cfg(test)]
+fn list_nested_entries(
+    archive_file: PathBuf,
+    depth: usize,
+    limits: &ArchiveLimits,
+) -> Result<Vec<String>> {
+    list_nested_contents(archive_file, depth, limits, &|_| false).map(|listing| listing.entries)
+}
+
+/// list_nested_contents returns the entries of the archive file, and the entries of the nested archives
+/// up to the given depth, with the contents of the files for which `wants` returns true.
+/// The entries of the nested archives are joined to the path of the nested archive with [SEPARATOR].
+/// The archive file is read only once, and the nested archives and the wanted contents are read
+/// while reading the archive file. They are charged to the limits together with the listing.
+/// The nested archives failed to read are skipped with the warnings in the log,
+/// while exceeding the limits fails the whole listing.
+/// The entries with the absolute paths or the parent directory traversals (`..`) are skipped as unsafe.
+pub(crate) fn list_nested_contents(
+    archive_file: PathBuf,
+    depth: usize,
+    limits: &ArchiveLimits,
+    wants: &dyn Fn(&str) -> bool,
+) -> Result<Listing> {
+    let budget = Budget::new(limits);
+    let mut result = Listing::default();
+    let format = find_format(archive_file.clone())?;
+    collect_entries(
+        format,
+        Input::File(&archive_file),
+        None,
+        depth,
+        wants,
+        &budget,
+        &mut result,
+    )
+    .map_err(|e| with_context(&archive_file.display(), e))?;
+    Ok(result)
+}
+
+/// collect_entries appends the entries of the given archive to `result` with the given prefix.
+fn collect_entries(
+    format: Format,
+    input: Input,
+    prefix: Option<&str>,
+    depth: usize,
+    wants: &dyn Fn(&str) -> bool,
+    budget: &Budget,
+    result: &mut Listing,
+) -> Result<()> {
+    let extractor = build_extractor(format)?;
+    extractor.visit_entries(input, budget, &mut |entry, read| {
+        let name = match prefix {
+            Some(prefix) => format!("{}{}{}", prefix, SEPARATOR, entry),
+            None => entry.to_string(),
+        };
+        result.entries.push(name.clone());
+        let format = nested_format(entry).filter(|_| depth > 0);
+        let wanted = !entry.ends_with('/') && wants(&name);
+        if format.is_none() && !wanted {
+            return Ok(());
+        }
+        let data = match read() {
+            Ok(data) => data,
+            Err(e) if budget.is_exceeded() => return Err(e),
+            Err(e) => {
+                log::warn!("{}: reading: {:?}", name, e);
+                return Ok(());
+            }
+        };
+        if let Some(format) = format {
+            let mut nested = Listing::default();
+            let r = collect_entries(
+                format,
+                Input::Bytes(&data),
+                Some(&name),
+                depth - 1,
+                wants,
+                budget,
+                &mut nested,
+            );
+            match r {
+                Ok(()) => {
+                    result.entries.extend(nested.entries);
+                    result.contents.extend(nested.contents);
+                }
+                Err(e) if budget.is_exceeded() => return Err(e),
+                Err(e) => log::warn!("{}: listing: {:?}", name, e),
+            }
+        }
+        if wanted {
+            result.contents.insert(name, data);
+        }
+        Ok(())
+    })
 }
 
-pub fn list_entries(archive_file: PathBuf) -> Result<Vec<String>> {
-    match find_format(archive_file.clone()) {
-        Ok(format) => match build_extractor(format) {
-            Ok(extractor) => extractor.list_entries(archive_file),
-            Err(e) => Err(e),
-        },
-        Err(e) => Err(e),
+/// nested_format returns the format of the given entry, if the entry is an archive file.
+fn nested_format(entry: &str) -> Option<Format> {
+    if entry.ends_with('/') {
+        None
+    } else {
+        find_format_impl(Path::new(entry).file_name()).ok()
     }
 }
 
 fn build_extractor(format: Format) -> Result<Box<dyn Extractor>> {
+    use ar::{ArExtractor, DebExtractor};
     use tar::{TarBz2Extractor, TarExtractor, TarGzExtractor, TarXzExtractor, TarZstdExtractor};
     use zip::ZipExtractor;
 
@@ -42,6 +305,8 @@ fn build_extractor(format: Format) -> Result<Box<dyn Extractor>> {
         Format::TarXz => Ok(Box::new(TarXzExtractor {})),
         Format::TarZstd => Ok(Box::new(TarZstdExtractor {})),
         Format::Zip => Ok(Box::new(ZipExtractor {})),
+        Format::Ar => Ok(Box::new(ArExtractor {})),
+        Format::Deb => Ok(Box::new(DebExtractor {})),
     }
 }
 
@@ -52,20 +317,73 @@ fn find_format(path: PathBuf) -> Result<Format> {
 fn find_format_impl(file_name: Option<&OsStr>) -> Result<Format> {
     match file_name {
         Some(file_name) => {
-            let name = file_name.to_str().unwrap().to_lowercase();
+            let name = file_name.to_string_lossy().to_lowercase();
             for ext in exts().iter() {
                 if name.ends_with(&ext.1) {
                     return Ok(ext.0.clone());
                 }
             }
             Err(MeisterError::UnsupportedArchiveFormat(
-                file_name.to_str().unwrap().to_string(),
+                file_name.to_string_lossy().to_string(),
             ))
         }
         None => Err(MeisterError::NoProjectSpecified()),
     }
 }
 
+/// is_unsafe_path returns true if the given entry path is absolute, or traverses the parent directory.
+fn is_unsafe_path(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    name.starts_with(['/', '\\'])
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+        || name.split(['/', '\\']).any(|c| c == "..")
+}
+
+/// read_limited reads the given reader to the end, charging the bytes to the budget.
+fn read_limited<R: Read>(reader: R, budget: &Budget) -> Result<Vec<u8>> {
+    let mut buf = vec![];
+    match LimitedReader::new(reader, budget).read_to_end(&mut buf) {
+        Ok(_) => Ok(buf),
+        Err(e) => Err(archive_error(e)),
+    }
+}
+
+/// LimitedReader charges the bytes read from the inner reader to the [Budget],
+/// and fails when the budget is exceeded.
+struct LimitedReader<'b, R> {
+    inner: R,
+    budget: &'b Budget<'b>,
+}
+
+impl<'b, R: Read> LimitedReader<'b, R> {
+    fn new(inner: R, budget: &'b Budget<'b>) -> Self {
+        LimitedReader { inner, budget }
+    }
+}
+
+impl<R: Read> Read for LimitedReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.budget.charge(n as u64)?;
+        Ok(n)
+    }
+}
+
+/// archive_error converts the error of reading the archive into [MeisterError::Archive].
+fn archive_error<E: Display>(e: E) -> MeisterError {
+    MeisterError::Archive(e.to_string())
+}
+
+/// with_context prefixes the name of the archive file to the message of [MeisterError::Archive].
+fn with_context<D: Display + ?Sized>(archive: &D, e: MeisterError) -> MeisterError {
+    match e {
+        MeisterError::Archive(message) => {
+            MeisterError::Archive(format!("{}: {}", archive, message))
+        }
+        e => e,
+    }
+}
+
 pub(super) fn exts() -> Vec<(Format, String)> {
     vec![
         (Format::Tar, String::from(".tar")),
@@ -81,5 +399,215 @@ pub(super) fn exts() -> Vec<(Format, String)> {
         (Format::Zip, String::from(".jar")),
         (Format::Zip, String::from(".war")),
         (Format::Zip, String::from(".ear")),
+        (Format::Ar, String::from(".ar")),
+        (Format::Deb, String::from(".deb")),
+        (Format::Deb, String::from(".udeb")),
     ]
 }
+
+/// list_input returns the entries of the given archive without the nested archives.
+#[cfg(test)]
+fn list_input(format: Format, input: Input, limits: &ArchiveLimits) -> Result<Vec<String>> {
+    let mut result = Listing::default();
+    collect_entries(
+        format,
+        input,
+        None,
+        0,
+        &|_| false,
+        &Budget::new(limits),
+        &mut result,
+    )?;
+    Ok(result.entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_entries() {
+        let file = PathBuf::from("../testdata/nested.tar.gz");
+        let limits = ArchiveLimits::default();
+        assert_eq!(
+            3,
+            list_nested_entries(file.clone(), 0, &limits).unwrap().len()
+        );
+
+        let entries = list_nested_entries(file.clone(), 1, &limits).unwrap();
+        assert!(entries.contains(&"nested/lib/app.jar!/pom.xml".to_string()));
+        assert!(entries.contains(&"nested/lib/bundle.zip!/inner/lib.jar".to_string()));
+        assert!(!entries.iter().any(|e| e.ends_with("build.gradle")));
+
+        let entries = list_nested_entries(file.clone(), 2, &limits).unwrap();
+        assert!(entries.contains(&"nested/lib/bundle.zip!/inner/lib.jar!/build.gradle".to_string()));
+
+        let listing =
+            list_nested_contents(file, 2, &limits, &|name| name.ends_with(".gradle")).unwrap();
+        assert_eq!(
+            vec!["nested/lib/bundle.zip!/inner/lib.jar!/build.gradle"],
+            listing.contents.keys().collect::<Vec<&String>>()
+        );
+        assert!(listing.contents.values().all(|c| c.starts_with(b"plugins")));
+    }
+
+    #[test]
+    fn test_nested_limits() {
+        let file = PathBuf::from("../testdata/nested.tar.gz");
+        let limits = ArchiveLimits::default();
+        let budget = Budget::new(&limits);
+        collect_entries(
+            Format::TarGz,
+            Input::File(&file),
+            None,
+            0,
+            &|_| false,
+            &budget,
+            &mut Listing::default(),
+        )
+        .unwrap();
+        // the budget is enough for nested.tar.gz itself, but not for the 146 bytes of inner/lib.jar in bundle.zip.
+        let limits = ArchiveLimits {
+            max_bytes: budget.bytes.get() + 100,
+            ..ArchiveLimits::default()
+        };
+        assert!(list_nested_entries(file.clone(), 0, &limits).is_ok());
+        assert!(list_nested_entries(file.clone(), 1, &limits).is_ok());
+        match list_nested_entries(file.clone(), 2, &limits) {
+            Err(MeisterError::Archive(m)) => assert!(m.contains("exceeds"), "{}", m),
+            r => panic!("unexpected result: {:?}", r),
+        }
+
+        let limits = ArchiveLimits {
+            max_entries: 5,
+            ..ArchiveLimits::default()
+        };
+        assert!(list_nested_entries(file.clone(), 0, &limits).is_ok());
+        assert!(matches!(
+            list_nested_entries(file, 2, &limits),
+            Err(MeisterError::Archive(_))
+        ));
+
+        // the nested zip file with too many entries fails the whole listing, like the tar files.
+        let zip = std::fs::read("../testdata/fibonacci.zip").unwrap();
+        let mut builder = ::tar::Builder::new(vec![]);
+        let mut header = ::tar::Header::new_gnu();
+        header.set_size(zip.len() as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "lib/app.jar", zip.as_slice())
+            .unwrap();
+        let data = builder.into_inner().unwrap();
+        let limits = ArchiveLimits {
+            max_entries: 10,
+            ..ArchiveLimits::default()
+        };
+        let result = collect_entries(
+            Format::Tar,
+            Input::Bytes(&data),
+            None,
+            1,
+            &|_| false,
+            &Budget::new(&limits),
+            &mut Listing::default(),
+        );
+        match result {
+            Err(MeisterError::Archive(m)) => assert!(m.contains("more than 10 entries"), "{}", m),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    fn tar_with(names: &[&str]) -> Vec<u8> {
+        let mut builder = ::tar::Builder::new(vec![]);
+        for name in names {
+            let mut header = ::tar::Header::new_gnu();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(3);
+            header.set_cksum();
+            builder.append(&header, "abc".as_bytes()).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_unsafe_entries() {
+        let data = tar_with(&[
+            "ok/pom.xml",
+            "../evil/pom.xml",
+            "/etc/Makefile",
+            "C:/x/Makefile",
+        ]);
+        let limits = ArchiveLimits::default();
+        assert_eq!(
+            vec!["ok/pom.xml".to_string()],
+            list_input(Format::Tar, Input::Bytes(&data), &limits).unwrap()
+        );
+        assert!(is_unsafe_path("a/..\\b"));
+        assert!(!is_unsafe_path("a/..b/c"));
+    }
+
+    #[test]
+    fn test_limits() {
+        let data = tar_with(&["a/Makefile", "a/pom.xml", "a/build.gradle"]);
+        let limits = ArchiveLimits {
+            max_entries: 2,
+            ..ArchiveLimits::default()
+        };
+        assert!(matches!(
+            list_input(Format::Tar, Input::Bytes(&data), &limits),
+            Err(MeisterError::Archive(_))
+        ));
+        let limits = ArchiveLimits {
+            max_bytes: 1024,
+            ..ArchiveLimits::default()
+        };
+        assert!(matches!(
+            list_input(Format::Tar, Input::Bytes(&data), &limits),
+            Err(MeisterError::Archive(_))
+        ));
+        let limits = ArchiveLimits {
+            max_path_length: 8,
+            ..ArchiveLimits::default()
+        };
+        assert!(matches!(
+            list_input(Format::Tar, Input::Bytes(&data), &limits),
+            Err(MeisterError::Archive(_))
+        ));
+
+        let file = PathBuf::from("../testdata/fibonacci.zip");
+        let limits = ArchiveLimits {
+            max_bytes: 16,
+            ..ArchiveLimits::default()
+        };
+        assert_eq!(
+            16,
+            list_nested_entries(file.clone(), 0, &limits).unwrap().len()
+        );
+        match list_nested_contents(file, 0, &limits, &|name| name.ends_with("build.gradle")) {
+            Err(MeisterError::Archive(m)) => assert!(m.starts_with("../testdata/fibonacci.zip: ")),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_corrupt_archives() {
+        let limits = ArchiveLimits::default();
+        let zip = std::fs::read("../testdata/fibonacci.zip").unwrap();
+        let gz = std::fs::read("../testdata/hello.tar.gz").unwrap();
+        for (format, data) in [
+            (Format::Zip, &zip[..zip.len() / 2]),
+            (Format::Zip, &b"PK\x03\x04broken"[..]),
+            (Format::TarGz, &gz[..gz.len() / 2]),
+            (Format::TarXz, &gz[..]),
+        ] {
+            assert!(
+                matches!(
+                    list_input(format.clone(), Input::Bytes(data), &limits),
+                    Err(MeisterError::Archive(_))
+                ),
+                "{:?}",
+                format
+            );
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::Read;
+
+use crate::extractors::{
+    archive_error, build_extractor, find_format_impl, Budget, Extractor, Input, Visitor, SEPARATOR,
+};
+use crate::{MeisterError, Result};
+
+#[cfg(test)]
+use crate::extractors::Format;
+
+/// the global header of the ar archives.
+const MAGIC: &[u8] = b"!<arch>\n";
+/// the length of the header of each member.
+const HEADER_LEN: usize = 60;
+
+pub(super) struct ArExtractor {}
+
+/// DebExtractor lists the entries of the Debian packages.
+/// The entries of `control.tar.*` and `data.tar.*` members are listed under the member names,
+/// e.g., `data.tar.xz!/usr/share/doc/hello/copyright`, instead of the members themselves.
+pub(super) struct DebExtractor {}
+
+impl Extractor for ArExtractor {
+    fn visit_entries(&self, input: Input, budget: &Budget, visitor: &mut Visitor) -> Result<()> {
+        let data = read_input(input, budget)?;
+        for (name, body) in parse_ar(&data)? {
+            if budget.accept_entry(&name)? {
+                visitor(&name, &mut || Ok(body.to_vec()))?;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn format(&self) -> Format {
+        Format::Ar
+    }
+}
+
+impl Extractor for DebExtractor {
+    fn visit_entries(&self, input: Input, budget: &Budget, visitor: &mut Visitor) -> Result<()> {
+        let data = read_input(input, budget)?;
+        for (name, body) in parse_ar(&data)? {
+            if !budget.accept_entry(&name)? {
+                continue;
+            }
+            if !name.starts_with("control.tar") && !name.starts_with("data.tar") {
+                visitor(&name, &mut || Ok(body.to_vec()))?;
+                continue;
+            }
+            let extractor = match find_format_impl(Some(OsStr::new(&name))) {
+                Ok(format) => build_extractor(format)?,
+                Err(_) => {
+                    log::warn!("{}: unsupported compression, skipped", name);
+                    continue;
+                }
+            };
+            extractor.visit_entries(Input::Bytes(body), budget, &mut |entry, read| {
+                visitor(&format!("{}{}{}", name, SEPARATOR, entry), read)
+            })?;
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn format(&self) -> Format {
+        Format::Deb
+    }
+}
+
+/// read_input reads the whole archive file, since the members of the ar archives are stored without compression.
+/// The bytes read from the file are charged to the budget.
+fn read_input<'a>(input: Input<'a>, budget: &Budget) -> Result<Cow<'a, [u8]>> {
+    let path = match input {
+        Input::File(path) => path,
+        Input::Bytes(data) => return Ok(Cow::Borrowed(data)),
+    };
+    let file = File::open(path).map_err(MeisterError::IO)?;
+    let size = file.metadata().map_err(MeisterError::IO)?.len();
+    budget.charge(size).map_err(archive_error)?;
+    let mut buf = Vec::with_capacity(size as usize);
+    match file.take(size).read_to_end(&mut buf) {
+        Ok(_) => Ok(Cow::Owned(buf)),
+        Err(e) => Err(MeisterError::IO(e)),
+    }
+}
+
+/// parse_ar returns the names and the contents of the members in the ar archive.
+/// The symbol tables and the long name tables of the GNU and BSD variants are not included in the result.
+fn parse_ar(data: &[u8]) -> Result<Vec<(String, &[u8])>> {
+    if !data.starts_with(MAGIC) {
+        return Err(MeisterError::Archive("not an ar archive".to_string()));
+    }
+    let mut result = vec![];
+    let mut long_names: &[u8] = &[];
+    let mut offset = MAGIC.len();
+    while offset < data.len() {
+        let at = offset;
+        let header = match data.get(offset..offset + HEADER_LEN) {
+            Some(header) if &header[58..60] == b"`\n" => header,
+            Some(_) => return Err(archive_error(format!("invalid header at {}", at))),
+            None => return Err(archive_error(format!("truncated header at {}", at))),
+        };
+        let size = parse_number(&header[48..58])
+            .ok_or_else(|| archive_error(format!("invalid size at {}", at)))?;
+        let start = offset + HEADER_LEN;
+        let mut body = match start.checked_add(size).and_then(|end| data.get(start..end)) {
+            Some(body) => body,
+            None => return Err(archive_error(format!("truncated member at {}", at))),
+        };
+        offset = start + size + size % 2;
+
+        let name = String::from_utf8_lossy(&header[0..16])
+            .trim_end()
+            .to_string();
+        let name = if name == "/" || name == "/SYM64/" || name.starts_with("__.SYMDEF") {
+            continue;
+        } else if name == "//" {
+            long_names = body;
+            continue;
+        } else if let Some(len) = name.strip_prefix("#1/") {
+            // BSD variant: the name is stored at the head of the content.
+            let len = parse_number(len.as_bytes())
+                .filter(|len| *len <= body.len())
+                .ok_or_else(|| archive_error(format!("invalid name at {}", at)))?;
+            let (name, rest) = body.split_at(len);
+            body = rest;
+            String::from_utf8_lossy(name)
+                .trim_end_matches('\0')
+                .to_string()
+        } else if let Some(index) = name.strip_prefix('/') {
+            // GNU variant: the name is stored in the long name table.
+            let name = parse_number(index.as_bytes())
+                .and_then(|index| long_names.get(index..))
+                .ok_or_else(|| archive_error(format!("invalid name at {}", at)))?;
+            let end = name.iter().position(|&b| b == b'\n').unwrap_or(name.len());
+            String::from_utf8_lossy(&name[..end])
+                .trim_end_matches('/')
+                .to_string()
+        } else {
+            name.trim_end_matches('/').to_string()
+        };
+        result.push((name, body));
+    }
+    Ok(result)
+}
+
+fn parse_number(field: &[u8]) -> Option<usize> {
+    std::str::from_utf8(field).ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractors::{list_input, ArchiveLimits};
+    use std::path::PathBuf;
+
+    fn member(name: &str, body: &[u8]) -> Vec<u8> {
+        let mut result = format!(
+            "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+            name,
+            0,
+            0,
+            0,
+            644,
+            body.len()
+        )
+        .into_bytes();
+        result.extend_from_slice(body);
+        if body.len() % 2 == 1 {
+            result.push(b'\n');
+        }
+        result
+    }
+
+    #[test]
+    fn test_list_ar() {
+        let long_name = "a_very_long_member_name.o";
+        let mut data = MAGIC.to_vec();
+        data.extend(member("/", b"symbols"));
+        data.extend(member("//", format!("{}/\n", long_name).as_bytes()));
+        data.extend(member("short.o/", b"abc"));
+        data.extend(member("/0", b"long"));
+        data.extend(member("#1/12", b"bsd_name.txtcontent"));
+        let extractor = ArExtractor {};
+        let limits = ArchiveLimits::default();
+        let budget = Budget::new(&limits);
+        assert_eq!(
+            vec!["short.o", long_name, "bsd_name.txt"],
+            list_input(Format::Ar, Input::Bytes(&data), &limits).unwrap()
+        );
+        let mut contents = vec![];
+        extractor
+            .visit_entries(Input::Bytes(&data), &budget, &mut |name, read| {
+                if name != long_name {
+                    contents.push((name.to_string(), read()?));
+                }
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(
+            vec![
+                ("short.o".to_string(), b"abc".to_vec()),
+                ("bsd_name.txt".to_string(), b"content".to_vec()),
+            ],
+            contents
+        );
+        assert!(matches!(
+            list_input(Format::Ar, Input::Bytes(&data[..data.len() - 4]), &limits),
+            Err(MeisterError::Archive(_))
+        ));
+        assert!(matches!(
+            list_input(Format::Ar, Input::Bytes(b"PK\x03\x04"), &limits),
+            Err(MeisterError::Archive(_))
+        ));
+    }
+
+    #[test]
+    fn test_list_deb() {
+        let extractor = DebExtractor {};
+        let file = PathBuf::from("../testdata/hello.deb");
+        let entries = extractor
+            .list_entries(file.clone(), &ArchiveLimits::default())
+            .unwrap();
+        assert_eq!(
+            vec![
+                "debian-binary",
+                "control.tar.gz!/control",
+                "data.tar.xz!/usr/",
+                "data.tar.xz!/usr/src/",
+                "data.tar.xz!/usr/src/hello/",
+                "data.tar.xz!/usr/src/hello/Cargo.toml",
+                "data.tar.xz!/usr/src/hello/src/",
+                "data.tar.xz!/usr/src/hello/src/main.rs",
+            ],
+            entries
+        );
+        let listing =
+            crate::extractors::list_nested_contents(file, 0, &ArchiveLimits::default(), &|name| {
+                name.ends_with("Cargo.toml")
+            })
+            .unwrap();
+        assert!(listing
+            .contents
+            .get("data.tar.xz!/usr/src/hello/Cargo.toml")
+            .is_some_and(|c| c.starts_with(b"[package]")));
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(ArExtractor {}.format(), Format::Ar);
+        assert_eq!(DebExtractor {}.format(), Format::Deb);
+        assert_eq!(
+            Format::Ar,
+            find_format_impl(Some(OsStr::new("files.ar"))).unwrap()
+        );
+        // the static libraries are not the archives of the projects.
+        assert!(matches!(
+            find_format_impl(Some(OsStr::new("libhello.a"))),
+            Err(MeisterError::UnsupportedArchiveFormat(_))
+        ));
+    }
+}
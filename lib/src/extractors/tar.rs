@@ -1,11 +1,10 @@
 use std::io::Read;
-use std::{fs::File, path::PathBuf};
 
 use tar::Archive;
 use xz2::read::XzDecoder;
 
-use crate::extractors::Extractor;
-use crate::{MeisterError, Result};
+use crate::extractors::{archive_error, Budget, Extractor, Input, LimitedReader, Visitor};
+use crate::Result;
 
 #[cfg(test)]
 use crate::extractors::Format;
@@ -16,107 +15,123 @@ pub(super) struct TarBz2Extractor {}
 pub(super) struct TarXzExtractor {}
 pub(super) struct TarZstdExtractor {}
 
-impl Extractor for TarExtractor {
-    fn list_entries(&self, archive_file: PathBuf) -> Result<Vec<String>> {
-        match open_tar_file(&archive_file, |f| f) {
-            Ok(archive) => list_tar(archive),
-            Err(e) => Err(e),
-        }
+/// Decoder decompresses the tar stream of each format.
+/// The extractors for the tar formats are implemented on the decoders.
+pub(super) trait Decoder {
+    fn decode<'a>(&self, reader: Box<dyn Read + 'a>) -> Result<Box<dyn Read + 'a>>;
+
+    #[cfg(test)]
+    fn tar_format(&self) -> Format;
+}
+
+impl Decoder for TarExtractor {
+    fn decode<'a>(&self, reader: Box<dyn Read + 'a>) -> Result<Box<dyn Read + 'a>> {
+        Ok(reader)
     }
 
     #[cfg(test)]
-    fn format(&self) -> Format {
+    fn tar_format(&self) -> Format {
         Format::Tar
     }
 }
 
-impl Extractor for TarGzExtractor {
-    fn list_entries(&self, archive_file: PathBuf) -> Result<Vec<String>> {
-        match open_tar_file(&archive_file, flate2::read::GzDecoder::new) {
-            Ok(archive) => list_tar(archive),
-            Err(e) => Err(e),
-        }
+impl Decoder for TarGzExtractor {
+    fn decode<'a>(&self, reader: Box<dyn Read + 'a>) -> Result<Box<dyn Read + 'a>> {
+        Ok(Box::new(flate2::read::GzDecoder::new(reader)))
     }
 
     #[cfg(test)]
-    fn format(&self) -> Format {
+    fn tar_format(&self) -> Format {
         Format::TarGz
     }
 }
 
-impl Extractor for TarBz2Extractor {
-    fn list_entries(&self, archive_file: PathBuf) -> Result<Vec<String>> {
-        match open_tar_file(&archive_file, bzip2::read::BzDecoder::new) {
-            Ok(archive) => list_tar(archive),
-            Err(e) => Err(e),
-        }
+impl Decoder for TarBz2Extractor {
+    fn decode<'a>(&self, reader: Box<dyn Read + 'a>) -> Result<Box<dyn Read + 'a>> {
+        Ok(Box::new(bzip2::read::BzDecoder::new(reader)))
     }
 
     #[cfg(test)]
-    fn format(&self) -> Format {
+    fn tar_format(&self) -> Format {
         Format::TarBz2
     }
 }
 
-impl Extractor for TarXzExtractor {
-    fn list_entries(&self, archive_file: PathBuf) -> Result<Vec<String>> {
-        match open_tar_file(&archive_file, XzDecoder::new) {
-            Err(e) => Err(e),
-            Ok(archive) => list_tar(archive),
-        }
+impl Decoder for TarXzExtractor {
+    fn decode<'a>(&self, reader: Box<dyn Read + 'a>) -> Result<Box<dyn Read + 'a>> {
+        Ok(Box::new(XzDecoder::new(reader)))
     }
 
     #[cfg(test)]
-    fn format(&self) -> Format {
+    fn tar_format(&self) -> Format {
         Format::TarXz
     }
 }
 
-impl Extractor for TarZstdExtractor {
-    fn list_entries(&self, archive_file: PathBuf) -> Result<Vec<String>> {
-        match open_tar_file(&archive_file, |f| zstd::Decoder::new(f).unwrap()) {
-            Err(e) => Err(e),
-            Ok(archive) => list_tar(archive),
+impl Decoder for TarZstdExtractor {
+    fn decode<'a>(&self, reader: Box<dyn Read + 'a>) -> Result<Box<dyn Read + 'a>> {
+        match zstd::Decoder::new(reader) {
+            Ok(decoder) => Ok(Box::new(decoder)),
+            Err(e) => Err(archive_error(e)),
         }
     }
 
     #[cfg(test)]
-    fn format(&self) -> Format {
+    fn tar_format(&self) -> Format {
         Format::TarZstd
     }
 }
 
-fn open_tar_file<F, R: Read>(file: &PathBuf, opener: F) -> Result<Archive<R>>
-where
-    F: FnOnce(File) -> R,
-{
-    let file = match File::open(file) {
-        Ok(f) => f,
-        Err(e) => return Err(MeisterError::IO(e)),
-    };
-    let writer = opener(file);
-    Ok(Archive::new(writer))
-}
+impl<D: Decoder> Extractor for D {
+    fn visit_entries(&self, input: Input, budget: &Budget, visitor: &mut Visitor) -> Result<()> {
+        let mut archive = open_tar(self, input, budget)?;
+        for entry in archive.entries().map_err(archive_error)? {
+            let mut entry = entry.map_err(archive_error)?;
+            let path = String::from_utf8_lossy(&entry.path_bytes()).to_string();
+            let path = path.trim_start_matches("./");
+            if path.is_empty() || !budget.accept_entry(path)? {
+                continue;
+            }
+            // the content is charged to the budget by the reader of the archive.
+            visitor(path, &mut || {
+                let mut buf = vec![];
+                match entry.read_to_end(&mut buf) {
+                    Ok(_) => Ok(buf),
+                    Err(e) => Err(archive_error(e)),
+                }
+            })?;
+        }
+        Ok(())
+    }
 
-fn list_tar<R: Read>(mut archive: tar::Archive<R>) -> Result<Vec<String>> {
-    let mut result = Vec::<String>::new();
-    for entry in archive.entries().unwrap() {
-        let entry = entry.unwrap();
-        let path = entry.header().path().unwrap();
-        result.push(format!("{}", path.to_string_lossy()));
+    #[cfg(test)]
+    fn format(&self) -> Format {
+        self.tar_format()
     }
-    Ok(result)
+}
+
+/// open_tar opens the tar archive from the stream decompressed by the given decoder,
+/// and charges the decompressed bytes read from the stream to the budget.
+fn open_tar<'a, D: Decoder>(
+    decoder: &D,
+    input: Input<'a>,
+    budget: &'a Budget<'a>,
+) -> Result<Archive<LimitedReader<'a, Box<dyn Read + 'a>>>> {
+    let decoded = decoder.decode(input.open()?)?;
+    Ok(Archive::new(LimitedReader::new(decoded, budget)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::extractors::{ArchiveLimits, Budget};
+    use std::path::{Path, PathBuf};
 
     #[test]
     fn test_list_tar_file() {
         let extractor = TarExtractor {};
         let file = PathBuf::from("../testdata/hello.tar");
-        match extractor.list_entries(file) {
+        match extractor.list_entries(file, &ArchiveLimits::default()) {
             Ok(r) => {
                 println!("{:?}", r);
                 assert_eq!(r.len(), 2);
@@ -131,7 +146,7 @@ mod tests {
     fn test_list_tarbz2_file() {
         let extractor = TarBz2Extractor {};
         let file = PathBuf::from("../testdata/hello.tar.bz2");
-        match extractor.list_entries(file) {
+        match extractor.list_entries(file, &ArchiveLimits::default()) {
             Ok(r) => {
                 assert_eq!(r.len(), 2);
                 assert_eq!(r.first(), Some("hello/Cargo.toml".to_string()).as_ref());
@@ -145,7 +160,7 @@ mod tests {
     fn test_list_targz_file() {
         let extractor = TarGzExtractor {};
         let file = PathBuf::from("../testdata/hello.tar.gz");
-        match extractor.list_entries(file) {
+        match extractor.list_entries(file, &ArchiveLimits::default()) {
             Ok(r) => {
                 assert_eq!(r.len(), 2);
                 assert_eq!(r.first(), Some("hello/Cargo.toml".to_string()).as_ref());
@@ -159,7 +174,7 @@ mod tests {
     fn test_list_tarxz_file() {
         let extractor = TarXzExtractor {};
         let file = PathBuf::from("../testdata/hello.tar.xz");
-        match extractor.list_entries(file) {
+        match extractor.list_entries(file, &ArchiveLimits::default()) {
             Ok(r) => {
                 assert_eq!(r.len(), 2);
                 assert_eq!(r.first(), Some("hello/Cargo.toml".to_string()).as_ref());
@@ -173,7 +188,7 @@ mod tests {
     fn test_list_tarzstd_file() {
         let extractor = TarZstdExtractor {};
         let file = PathBuf::from("../testdata/hello.tar.zst");
-        match extractor.list_entries(file) {
+        match extractor.list_entries(file, &ArchiveLimits::default()) {
             Ok(r) => {
                 assert_eq!(r.len(), 2);
                 assert_eq!(r.first(), Some("hello/Cargo.toml".to_string()).as_ref());
@@ -183,6 +198,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_read_tar_entry() {
+        let extractor = TarGzExtractor {};
+        let file = Input::File(Path::new("../testdata/hello.tar.gz"));
+        let limits = ArchiveLimits::default();
+        let mut contents = vec![];
+        extractor
+            .visit_entries(file, &Budget::new(&limits), &mut |name, read| {
+                if name == "hello/Cargo.toml" {
+                    contents.push(read()?);
+                }
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(1, contents.len());
+        assert!(String::from_utf8_lossy(&contents[0]).contains("[package]"));
+    }
+
     #[test]
     fn test_format() {
         let e1 = TarExtractor {};
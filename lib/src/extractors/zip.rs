@@ -1,8 +1,8 @@
 use std::fs::File;
-use std::path::PathBuf;
+use std::io::{Cursor, Read, Seek};
 
-use crate::extractors::Extractor;
-use crate::Result;
+use crate::extractors::{archive_error, read_limited, Budget, Extractor, Input, Visitor};
+use crate::{MeisterError, Result};
 
 #[cfg(test)]
 use crate::extractors::Format;
@@ -10,17 +10,14 @@ use crate::extractors::Format;
 pub(super) struct ZipExtractor {}
 
 impl Extractor for ZipExtractor {
-    fn list_entries(&self, archive_file: PathBuf) -> Result<Vec<String>> {
-        let zip_file = File::open(archive_file).unwrap();
-        let mut zip = zip::ZipArchive::new(zip_file).unwrap();
-
-        let mut result = Vec::<String>::new();
-        for i in 0..zip.len() {
-            let file = zip.by_index(i).unwrap();
-            result.push(file.name().to_string());
-            // std::io::copy(&mut file, &mut std::io::stdout()).unwrap();
+    fn visit_entries(&self, input: Input, budget: &Budget, visitor: &mut Visitor) -> Result<()> {
+        match input {
+            Input::File(path) => match File::open(path) {
+                Ok(f) => visit_zip(f, budget, visitor),
+                Err(e) => Err(MeisterError::IO(e)),
+            },
+            Input::Bytes(data) => visit_zip(Cursor::new(data), budget, visitor),
         }
-        Ok(result)
     }
 
     #[cfg(test)]
@@ -29,16 +26,36 @@ impl Extractor for ZipExtractor {
     }
 }
 
+fn visit_zip<R: Read + Seek>(reader: R, budget: &Budget, visitor: &mut Visitor) -> Result<()> {
+    let mut zip = zip::ZipArchive::new(reader).map_err(archive_error)?;
+    budget.reserve_entries(zip.len())?;
+    for i in 0..zip.len() {
+        let name = match zip.name_for_index(i) {
+            Some(name) => name.to_string(),
+            None => return Err(MeisterError::Archive(format!("entry #{} not found", i))),
+        };
+        if !budget.accept_entry(&name)? {
+            continue;
+        }
+        visitor(&name, &mut || match zip.by_index(i) {
+            Ok(file) => read_limited(file, budget),
+            Err(e) => Err(archive_error(e)),
+        })?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::extractors::ArchiveLimits;
     use std::path::PathBuf;
 
     #[test]
     fn test_list_archives() {
         let extractor = ZipExtractor {};
         let file = PathBuf::from("../testdata/fibonacci.zip");
-        match extractor.list_entries(file) {
+        match extractor.list_entries(file, &ArchiveLimits::default()) {
             Ok(r) => {
                 assert_eq!(r.len(), 16);
                 assert_eq!(r.get(6), Some("fibonacci/.gitignore".to_string()).as_ref());
@@ -51,6 +68,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_read_entry() {
+        let extractor = ZipExtractor {};
+        let file = PathBuf::from("../testdata/fibonacci.zip");
+        let limits = ArchiveLimits::default();
+        let mut contents = vec![];
+        extractor
+            .visit_entries(
+                Input::File(&file),
+                &Budget::new(&limits),
+                &mut |name, read| {
+                    if name == "fibonacci/settings.gradle" {
+                        contents.push(read()?);
+                    }
+                    Ok(())
+                },
+            )
+            .unwrap();
+        assert_eq!(1, contents.len());
+        assert!(String::from_utf8_lossy(&contents[0]).contains("rootProject.name"));
+    }
+
     #[test]
     fn test_format() {
         let e = ZipExtractor {};
@@ -0,0 +1,375 @@
+/*!
+ * This module lists and reads the files of a revision in a git repository without checking it out.
+ * The files are read from the object database through the `git` command,
+ * therefore, the bare repositories are also supported.
+ *
+ * The revision is specified by appending `@` and the revision to the repository path,
+ * e.g., `path/to/repo.git@v1.2.0`.
+ */
+use std::cell::RefCell;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use crate::{MeisterError, Result};
+
+/// parse_revision splits the given argument into the git repository and the revision.
+/// If the argument is not the form of `repository@revision`, this function returns `None`.
+/// The argument is split at the first `@` following an existing repository,
+/// since the revisions may contain `@` (e.g., `HEAD@{1}`, and `main@{yesterday}`).
+pub fn parse_revision<P: AsRef<Path>>(arg: P) -> Option<(PathBuf, String)> {
+    let arg = arg.as_ref().to_str()?;
+    arg.match_indices('@').find_map(|(index, _)| {
+        let (repo, rev) = (PathBuf::from(&arg[..index]), &arg[index + 1..]);
+        if !rev.is_empty() && is_repository(&repo) {
+            Some((repo, rev.to_string()))
+        } else {
+            None
+        }
+    })
+}
+
+/// is_repository returns true if the given directory is a git repository (a working tree or a bare repository).
+pub fn is_repository(path: &Path) -> bool {
+    path.join(".git").exists() || (path.join("HEAD").is_file() && path.join("objects").is_dir())
+}
+
+/// list_entries returns the paths of the files in the tree of the given revision.
+pub(crate) fn list_entries(repo: &Path, rev: &str) -> Result<Vec<String>> {
+    let tree = format!("{}^{{tree}}", rev);
+    let output = git(
+        repo,
+        &[
+            "ls-tree",
+            "-r",
+            "-z",
+            "--name-only",
+            "--end-of-options",
+            &tree,
+        ],
+    )?;
+    Ok(output
+        .split(|b| *b == 0)
+        .filter(|name| !name.is_empty())
+        .map(|name| String::from_utf8_lossy(name).to_string())
+        .collect())
+}
+
+/// list_blobs returns the object names and the paths of the files in the tree of the given revision.
+pub(crate) fn list_blobs(repo: &Path, rev: &str) -> Result<Vec<(String, String)>> {
+    let tree = format!("{}^{{tree}}", rev);
+    let output = git(repo, &["ls-tree", "-r", "-z", "--end-of-options", &tree])?;
+    Ok(output
+        .split(|b| *b == 0)
+        .filter_map(|line| {
+            // each line is "<mode> SP <type> SP <object> TAB <path>".
+            let line = String::from_utf8_lossy(line);
+            let (info, path) = line.split_once('\t')?;
+            match info.split(' ').collect::<Vec<&str>>().as_slice() {
+                [_, "blob", object] => Some((object.to_string(), path.to_string())),
+                _ => None,
+            }
+        })
+        .collect())
+}
+
+/// Batch reads the files of the revisions in a git repository through a `git cat-file --batch` process.
+/// The process is started at the first read, and is shared by the following reads.
+pub(crate) struct Batch {
+    repo: PathBuf,
+    process: RefCell<Option<BatchProcess>>,
+}
+
+struct BatchProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Batch {
+    pub(crate) fn new(repo: &Path) -> Self {
+        Batch {
+            repo: repo.to_path_buf(),
+            process: RefCell::new(None),
+        }
+    }
+
+    /// repo returns the path of the git repository.
+    pub(crate) fn repo(&self) -> &Path {
+        &self.repo
+    }
+
+    /// read_entry returns the content of the file in the tree of the given revision.
+    /// If the file is not found, this function returns `Ok(None)`.
+    pub(crate) fn read_entry(&self, rev: &str, name: &str) -> Result<Option<Vec<u8>>> {
+        // the objects are given to the process line by line.
+        if rev.contains('\n') || name.contains('\n') {
+            return Ok(None);
+        }
+        let mut process = self.process.borrow_mut();
+        let p = match process.as_mut() {
+            Some(p) => p,
+            None => process.insert(BatchProcess::spawn(&self.repo)?),
+        };
+        let result = p.read(&format!("{}:{}", rev, name));
+        if result.is_err() {
+            // the process in the unknown state is restarted at the next read.
+            *process = None;
+        }
+        result
+    }
+}
+
+impl BatchProcess {
+    fn spawn(repo: &Path) -> Result<Self> {
+        log::trace!("git -C {} cat-file --batch", repo.display());
+        let mut child = Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(["cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(MeisterError::IO)?;
+        match (child.stdin.take(), child.stdout.take()) {
+            (Some(stdin), Some(stdout)) => Ok(BatchProcess {
+                child,
+                stdin,
+                stdout: BufReader::new(stdout),
+            }),
+            _ => Err(MeisterError::Git(format!(
+                "{}: cat-file: no pipes",
+                repo.display()
+            ))),
+        }
+    }
+
+    /// read returns the content of the given object, or `None` if it is missing or not a blob.
+    fn read(&mut self, object: &str) -> Result<Option<Vec<u8>>> {
+        writeln!(self.stdin, "{}", object)
+            .and_then(|_| self.stdin.flush())
+            .map_err(MeisterError::IO)?;
+        let mut header = String::new();
+        self.stdout
+            .read_line(&mut header)
+            .map_err(MeisterError::IO)?;
+        let header = header.trim_end_matches('\n');
+        if header.ends_with(" missing") || header.ends_with(" ambiguous") {
+            return Ok(None);
+        }
+        let invalid = || MeisterError::Git(format!("cat-file: invalid header: {}", header));
+        let (kind, size) = match header.split(' ').collect::<Vec<&str>>().as_slice() {
+            [_, kind, size] => (
+                kind.to_string(),
+                size.parse::<usize>().map_err(|_| invalid())?,
+            ),
+            _ => return Err(invalid()),
+        };
+        // the content is followed by a line feed.
+        let mut content = vec![0; size + 1];
+        self.stdout
+            .read_exact(&mut content)
+            .map_err(MeisterError::IO)?;
+        content.truncate(size);
+        Ok((kind == "blob").then_some(content))
+    }
+}
+
+impl Drop for BatchProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Commit represents a commit of the git repository.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commit {
+    /// the object name (SHA-1) of the commit.
+    pub id: String,
+    /// the tag name pointing to the commit, if the commit was listed from the tags.
+    pub tag: Option<String>,
+    /// the committer date (or the tagger date) in the strict ISO 8601 format.
+    pub date: String,
+}
+
+impl Commit {
+    /// label returns the tag name, or the abbreviated object name if no tag was given.
+    pub fn label(&self) -> &str {
+        match &self.tag {
+            Some(tag) => tag,
+            None => &self.id[..self.id.len().min(7)],
+        }
+    }
+}
+
+/// commits returns the commits of the given repository in the chronological order.
+/// If `tags_only` is true, this function returns only the tagged commits, otherwise,
+/// the first-parent history of `HEAD`.
+pub(crate) fn commits(repo: &Path, tags_only: bool) -> Result<Vec<Commit>> {
+    let output = if tags_only {
+        git(
+            repo,
+            &[
+                "for-each-ref",
+                "--sort=creatordate",
+                "--format=%(objectname)%00%(*objectname)%00%(creatordate:iso-strict)%00%(refname:short)",
+                "refs/tags",
+            ],
+        )?
+    } else {
+        git(
+            repo,
+            &[
+                "log",
+                "--reverse",
+                "--first-parent",
+                "--format=%H%x00%x00%cI%x00",
+                "HEAD",
+            ],
+        )?
+    };
+    Ok(String::from_utf8_lossy(&output)
+        .lines()
+        .filter_map(|line| {
+            let items = line.split('\0').collect::<Vec<&str>>();
+            match items.as_slice() {
+                [id, peeled, date, tag] => Some(Commit {
+                    id: if peeled.is_empty() { id } else { peeled }.to_string(),
+                    tag: if tag.is_empty() {
+                        None
+                    } else {
+                        Some(tag.to_string())
+                    },
+                    date: date.to_string(),
+                }),
+                _ => None,
+            }
+        })
+        .collect())
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    log::trace!("git -C {} {}", repo.display(), args.join(" "));
+    match Command::new("git").arg("-C").arg(repo).args(args).output() {
+        Ok(output) if output.status.success() => Ok(output.stdout),
+        Ok(output) => Err(MeisterError::Git(format!(
+            "{}: {}",
+            repo.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))),
+        Err(e) => Err(MeisterError::IO(e)),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// fixture creates a bare repository with three tagged commits in the temporary directory, and returns its path.
+    /// The tag `v1` contains `Makefile` only, `v2` contains `Makefile` and `build.gradle`,
+    /// and `v3` contains `build.gradle` only.
+    pub(crate) fn fixture(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("btmeister-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let work = dir.join("work");
+        std::fs::create_dir_all(&work).unwrap();
+        let run = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&work)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?}", args);
+        };
+        run(&["init", "-q"]);
+        std::fs::write(work.join("Makefile"), "all:\n").unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-q", "-m", "first"]);
+        run(&["tag", "v1"]);
+        std::fs::write(work.join("build.gradle"), "").unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-q", "-m", "second"]);
+        run(&["tag", "v2"]);
+        run(&["rm", "-q", "Makefile"]);
+        run(&["commit", "-q", "-m", "third"]);
+        run(&["tag", "-a", "-m", "third", "v3"]);
+        run(&["clone", "-q", "--bare", ".", "../repo.git"]);
+        dir.join("repo.git")
+    }
+
+    #[test]
+    fn test_list_and_read() {
+        let repo = fixture("git");
+        assert!(is_repository(&repo));
+        assert_eq!(
+            vec!["Makefile".to_string()],
+            list_entries(&repo, "v1").unwrap()
+        );
+        assert_eq!(
+            vec!["Makefile".to_string(), "build.gradle".to_string()],
+            list_entries(&repo, "v2").unwrap()
+        );
+        let batch = Batch::new(&repo);
+        assert_eq!(
+            Some(b"all:\n".to_vec()),
+            batch.read_entry("v1", "Makefile").unwrap()
+        );
+        assert_eq!(None, batch.read_entry("v1", "build.gradle").unwrap());
+        assert_eq!(
+            Some(vec![]),
+            batch.read_entry("v2", "build.gradle").unwrap()
+        );
+        assert_eq!(None, batch.read_entry("unknown", "Makefile").unwrap());
+        assert_eq!(None, batch.read_entry("v1", "").unwrap());
+        assert!(list_entries(&repo, "unknown").is_err());
+        let blobs = list_blobs(&repo, "v2").unwrap();
+        assert_eq!(
+            vec!["Makefile", "build.gradle"],
+            blobs.iter().map(|(_, p)| p.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!(list_blobs(&repo, "v1").unwrap()[0], blobs[0]);
+
+        // the revisions starting with `-` are not treated as the options.
+        let output = repo.with_extension("out");
+        let option = format!("--output={}", output.display());
+        assert!(list_entries(&repo, &option).is_err());
+        assert!(batch.read_entry(&option, "Makefile").unwrap().is_none());
+        assert!(!output.exists());
+
+        let commits = commits(&repo, false).unwrap();
+        assert_eq!(3, commits.len());
+        assert!(commits
+            .iter()
+            .all(|c| c.tag.is_none() && c.label().len() == 7));
+        let tags = super::commits(&repo, true).unwrap();
+        assert_eq!(
+            vec!["v1", "v2", "v3"],
+            tags.iter().map(|c| c.label()).collect::<Vec<&str>>()
+        );
+        assert_eq!(
+            commits.iter().map(|c| &c.id).collect::<Vec<_>>(),
+            tags.iter().map(|c| &c.id).collect::<Vec<_>>()
+        );
+
+        let arg = format!("{}@v1", repo.display());
+        assert_eq!(Some((repo.clone(), "v1".to_string())), parse_revision(&arg));
+        let arg = format!("{}@HEAD@{{1}}", repo.display());
+        assert_eq!(
+            Some((repo.clone(), "HEAD@{1}".to_string())),
+            parse_revision(&arg)
+        );
+        let arg = format!("{}@main@{{yesterday}}", repo.display());
+        assert_eq!(
+            Some((repo.clone(), "main@{yesterday}".to_string())),
+            parse_revision(&arg)
+        );
+        assert_eq!(None, parse_revision(format!("{}@", repo.display())));
+        assert_eq!(None, parse_revision("../testdata/hello@v1"));
+        let _ = std::fs::remove_dir_all(repo.parent().unwrap());
+    }
+}
@@ -0,0 +1,179 @@
+/*!
+ * This module builds the timeline of the build tools across the commits of a git repository.
+ * The build tools are detected in the tree of each commit by the same definitions as [Meister::find],
+ * and the timeline reports the commit where each build tool appeared and the commit where it disappeared.
+ *
+ * Since the build tool of a file depends only on its path and content, the files unchanged from the previous commit
+ * (the same paths and object names) reuse the previous results, and only the changed files are detected again.
+ *
+ * ```no_run
+ * let meister = btmeister::Meister::default();
+ * let timeline = meister.history(std::path::PathBuf::from("path/to/repo.git"), true).unwrap();
+ * for span in timeline.spans {
+ *     println!("{}: {} .. {:?}", span.name, span.appeared.label(), span.disappeared.map(|c| c.label().to_string()));
+ * }
+ * ```
+ */
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+pub use crate::git::Commit;
+use crate::{git, match_build_tool, Matcher, Meister, MeisterError, Result, Source};
+
+/// Detected maps the paths and the object names of the files to the names of their build tools.
+type Detected = HashMap<(String, String), Option<String>>;
+
+/// Timeline represents the history of the build tools in a git repository.
+#[derive(Clone, Debug)]
+pub struct Timeline {
+    /// the path of the git repository.
+    pub repository: PathBuf,
+    /// the scanned commits in the chronological order.
+    pub commits: Vec<Commit>,
+    /// the periods of the build tools in the order of their appearance.
+    pub spans: Vec<Span>,
+}
+
+/// Span represents a period in which a build tool was in use.
+/// A build tool may have multiple spans, if it was removed and introduced again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+    /// the name of the build tool definition.
+    pub name: String,
+    /// the first commit containing the build tool.
+    pub appeared: Commit,
+    /// the first commit not containing the build tool after it appeared,
+    /// or `None` if the build tool is still in use at the last scanned commit.
+    pub disappeared: Option<Commit>,
+}
+
+impl Meister {
+    /// history detects the build tools in each commit of the given git repository, and builds the [Timeline].
+    /// If `tags_only` is true, only the tagged commits are scanned in the order of the creation dates of the tags,
+    /// otherwise, the first-parent history of `HEAD` is scanned.
+    pub fn history(&self, repository: PathBuf, tags_only: bool) -> Result<Timeline> {
+        if !git::is_repository(&repository) {
+            return Err(MeisterError::NotProject(format!(
+                "{} (not a git repository)",
+                repository.display()
+            )));
+        }
+        let commits = git::commits(&repository, tags_only)?;
+        let batch = git::Batch::new(&repository);
+        let mut detected = Detected::new();
+        let mut spans = Vec::<Span>::new();
+        for commit in commits.iter() {
+            let names;
+            (names, detected) = self.tool_names(&batch, &commit.id, &detected)?;
+            for span in spans.iter_mut().filter(|s| s.disappeared.is_none()) {
+                if !names.contains(&span.name) {
+                    span.disappeared = Some(commit.clone());
+                }
+            }
+            for name in names {
+                if !spans
+                    .iter()
+                    .any(|s| s.name == name && s.disappeared.is_none())
+                {
+                    spans.push(Span {
+                        name,
+                        appeared: commit.clone(),
+                        disappeared: None,
+                    });
+                }
+            }
+        }
+        Ok(Timeline {
+            repository,
+            commits,
+            spans,
+        })
+    }
+
+    /// tool_names returns the names of the build tools in the tree of the given revision,
+    /// and the detection results of the files in the tree.
+    /// The results of the files in `previous` are reused.
+    fn tool_names(
+        &self,
+        batch: &git::Batch,
+        rev: &str,
+        previous: &Detected,
+    ) -> Result<(BTreeSet<String>, Detected)> {
+        let source = Source::Revision(batch, rev);
+        let mut detected = Detected::new();
+        for (object, path) in git::list_blobs(batch.repo(), rev)? {
+            let file = Path::new(&path);
+            if self.excludes.matches(file) {
+                continue;
+            }
+            let key = (path.clone(), object);
+            let name = match previous.get(&key) {
+                Some(name) => name.clone(),
+                None => {
+                    match_build_tool(self, file, file, &source, &mut None).map(|bt| bt.def.name)
+                }
+            };
+            detected.insert(key, name);
+        }
+        let names = detected.values().flatten().cloned().collect();
+        Ok((names, detected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history() {
+        let repo = git::tests::fixture("history");
+        let meister = Meister::default();
+        let timeline = meister.history(repo.clone(), true).unwrap();
+        assert_eq!(3, timeline.commits.len());
+        let spans = timeline
+            .spans
+            .iter()
+            .map(|s| {
+                (
+                    s.name.as_str(),
+                    s.appeared.label(),
+                    s.disappeared.as_ref().map(|c| c.label()),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![("Make", "v1", Some("v3")), ("Gradle", "v2", None)],
+            spans
+        );
+
+        let timeline = meister.history(repo.clone(), false).unwrap();
+        assert_eq!(2, timeline.spans.len());
+        assert!(timeline.spans[0].appeared.tag.is_none());
+
+        assert!(meister
+            .history(PathBuf::from("../testdata/hello"), false)
+            .is_err());
+        let _ = std::fs::remove_dir_all(repo.parent().unwrap());
+    }
+
+    #[test]
+    fn test_reuse_detected() {
+        let repo = git::tests::fixture("history-reuse");
+        let meister = Meister::default();
+        let batch = git::Batch::new(&repo);
+        let (names, detected) = meister.tool_names(&batch, "v1", &Detected::new()).unwrap();
+        assert_eq!(BTreeSet::from(["Make".to_string()]), names);
+
+        // the unchanged files are not detected again.
+        let tampered = detected
+            .into_keys()
+            .map(|key| (key, Some("Tampered".to_string())))
+            .collect::<Detected>();
+        let (names, _) = meister.tool_names(&batch, "v2", &tampered).unwrap();
+        assert_eq!(
+            BTreeSet::from(["Gradle".to_string(), "Tampered".to_string()]),
+            names
+        );
+        let _ = std::fs::remove_dir_all(repo.parent().unwrap());
+    }
+}
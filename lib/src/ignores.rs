@@ -0,0 +1,205 @@
+/*!
+ * This module applies the ignore types ([IgnoreType]) to the entries of the archive files,
+ * in the same manner as the directory traversal by [ignore::WalkBuilder].
+ *
+ * The ignore files (`.ignore`, `.gitignore`, and `.git/info/exclude`) are read from the archive file,
+ * and the rules of them are applied to the entries under the directory of the ignore files.
+ * Unlike the directories, `.gitignore` files in the archive files are respected without `.git` directories,
+ * since the archive files are usually created without them.
+ * The entries under the ignored directories, and the entries of the ignored nested archives are also ignored.
+ */
+use std::path::{Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+
+use crate::IgnoreType;
+
+/// the ignore files in the archive files, with the ignore types respecting them.
+const IGNORE_FILES: [(IgnoreType, &str); 3] = [
+    (IgnoreType::Ignore, ".ignore"),
+    (IgnoreType::GitIgnore, ".gitignore"),
+    (IgnoreType::GitExclude, ".git/info/exclude"),
+];
+
+/// is_ignore_file returns true if the given entry is an ignore file, whose content is needed by [ArchiveIgnore::new].
+pub(crate) fn is_ignore_file(entry: &str) -> bool {
+    IGNORE_FILES
+        .iter()
+        .any(|(_, file_name)| dir_of(entry, file_name).is_some())
+}
+
+/// dir_of returns the directory of the given entry if the entry is the ignore file of the given name.
+fn dir_of<'a>(entry: &'a str, file_name: &str) -> Option<&'a str> {
+    let dir = entry.strip_suffix(file_name)?;
+    (dir.is_empty() || dir.ends_with('/')).then_some(dir)
+}
+
+/// ArchiveIgnore decides the ignored entries of an archive file.
+pub(crate) struct ArchiveIgnore {
+    hidden: bool,
+    /// the rules with their directories, in the order of the precedence.
+    rules: Vec<(PathBuf, Gitignore)>,
+    global: Option<Gitignore>,
+}
+
+impl ArchiveIgnore {
+    /// new builds the rules from the ignore files in the given entries.
+    /// The contents of the ignore files are read by the given function.
+    pub(crate) fn new<F>(entries: &[String], its: &[IgnoreType], mut read: F) -> Self
+    where
+        F: FnMut(&str) -> Option<Vec<u8>>,
+    {
+        let mut rules = vec![];
+        for (it, file_name) in IGNORE_FILES {
+            if !its.contains(&it) {
+                continue;
+            }
+            let mut found = entries
+                .iter()
+                .filter_map(|entry| Some((entry, PathBuf::from(dir_of(entry, file_name)?))))
+                .filter_map(|(entry, dir)| read(entry).map(|content| (dir, content)))
+                .map(|(dir, content)| (dir.clone(), build_rules(&dir, &content)))
+                .collect::<Vec<_>>();
+            // the rules in the deeper directories precede the rules in the parent directories.
+            found.sort_by_key(|(dir, _)| std::cmp::Reverse(dir.components().count()));
+            rules.extend(found);
+        }
+        let global = if its.contains(&IgnoreType::GitGlobal) {
+            let (global, err) = Gitignore::global();
+            if let Some(e) = err {
+                log::warn!("global gitignore: {}", e);
+            }
+            Some(global)
+        } else {
+            None
+        };
+        ArchiveIgnore {
+            hidden: its.contains(&IgnoreType::Hidden),
+            rules,
+            global,
+        }
+    }
+
+    /// is_ignored returns true if the given entry, or one of its parent directories are ignored.
+    /// The nested archive is treated as the parent directory of its entries (`lib/app.jar!/pom.xml`).
+    pub(crate) fn is_ignored(&self, entry: &str) -> bool {
+        if !self.hidden && self.rules.is_empty() && self.global.is_none() {
+            return false;
+        }
+        let is_dir = entry.ends_with('/');
+        let entry = entry.trim_end_matches('/');
+        for (index, _) in entry.match_indices('/') {
+            let parent = &entry[..index];
+            let ignored = match parent.strip_suffix('!') {
+                Some(archive) => self.matches(archive, false),
+                None => self.matches(parent, true),
+            };
+            if ignored {
+                return true;
+            }
+        }
+        self.matches(entry, is_dir)
+    }
+
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        let path = Path::new(path);
+        if self.hidden
+            && path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        {
+            return true;
+        }
+        let found = self
+            .rules
+            .iter()
+            .filter(|(dir, _)| path.starts_with(dir))
+            .map(|(_, rules)| rules.matched(path, is_dir))
+            .chain(self.global.iter().map(|g| g.matched(path, is_dir)))
+            .find(|m| !m.is_none());
+        matches!(found, Some(Match::Ignore(_)))
+    }
+}
+
+fn build_rules(dir: &Path, content: &[u8]) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(dir);
+    for line in String::from_utf8_lossy(content).lines() {
+        if let Err(e) = builder.add_line(None, line) {
+            log::warn!("{}: {}", dir.display(), e);
+        }
+    }
+    builder.build().unwrap_or_else(|e| {
+        log::warn!("{}: {}", dir.display(), e);
+        Gitignore::empty()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_ignore_file() {
+        assert!(is_ignore_file(".gitignore"));
+        assert!(is_ignore_file("p/.ignore"));
+        assert!(is_ignore_file("lib/app.jar!/.git/info/exclude"));
+        assert!(!is_ignore_file("p/not.gitignore"));
+        assert!(!is_ignore_file("p/.gitignore/Makefile"));
+    }
+
+    #[test]
+    fn test_is_ignored() {
+        let entries = [
+            "p/.gitignore",
+            "p/.ignore",
+            "p/target/",
+            "p/target/pom.xml",
+            "p/sub/.gitignore",
+            "p/sub/Makefile",
+            "p/sub/build.gradle",
+            "p/.github/workflows/ci.yml",
+            "p/lib/app.jar",
+            "p/lib/app.jar!/pom.xml",
+        ]
+        .map(String::from);
+        let read = |name: &str| match name {
+            "p/.gitignore" => Some(b"target/\n*.gradle\n*.jar\n".to_vec()),
+            "p/.ignore" => Some(b"!*.jar\n".to_vec()),
+            "p/sub/.gitignore" => Some(b"!build.gradle\nMakefile\n".to_vec()),
+            _ => None,
+        };
+        let ignored = |its: &[IgnoreType]| {
+            let ignore = ArchiveIgnore::new(&entries, its, read);
+            entries
+                .iter()
+                .filter(|e| ignore.is_ignored(e))
+                .cloned()
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            vec!["p/target/", "p/target/pom.xml", "p/sub/Makefile"],
+            ignored(&[IgnoreType::Ignore, IgnoreType::GitIgnore])
+        );
+        assert_eq!(
+            vec![
+                "p/target/",
+                "p/target/pom.xml",
+                "p/sub/Makefile",
+                "p/lib/app.jar",
+                "p/lib/app.jar!/pom.xml",
+            ],
+            ignored(&[IgnoreType::GitIgnore])
+        );
+        assert_eq!(
+            vec![
+                "p/.gitignore",
+                "p/.ignore",
+                "p/sub/.gitignore",
+                "p/.github/workflows/ci.yml",
+            ],
+            ignored(&[IgnoreType::Hidden])
+        );
+        assert!(ignored(&[]).is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+/*!
+ * This is a module for the language census of the projects.
+ * The census counts the files of each language by the extensions of the file names.
+ * The mapping from the extensions to the languages is built in the same ways as [BuildToolDefs](crate::defs::BuildToolDefs),
+ * that is, from the asset file included in the library, or from the given file.
+ *
+ * The mapping file must be a JSON format file ([JSON schema](https://github.com/tamada/btmeister/blob/main/assets/languages.json.schema)).
+ * The example of the mapping file is as follows and the default mapping file is [here](https://github.com/tamada/btmeister/blob/main/assets/languages.json).
+ *
+ * ```json
+ * [
+ *   {
+ *     "name": "Rust",
+ *     "extensions": ["rs"]
+ *   }
+ * ]
+ * ```
+ *
+ * The extensions are compared ignoring the case, and the first definition wins if the extensions are duplicated.
+ */
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::defs::Asset;
+use crate::{MeisterError, Result};
+
+/// LanguageDef represents a language and the extensions of its files.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LanguageDef {
+    pub name: String,
+    pub extensions: Vec<String>,
+}
+
+/// LanguageDefs represents a collection of language definitions.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct LanguageDefs {
+    defs: Vec<LanguageDef>,
+}
+
+/// LanguageCount represents the number of the files of a language in a project.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LanguageCount {
+    /// the name of the language.
+    pub name: String,
+    /// the number of the files of the language.
+    pub files: usize,
+}
+
+impl Default for LanguageDefs {
+    /// The `default` function parses the language definitions from the asset file included in the library.
+    fn default() -> Self {
+        if let Some(f) = Asset::get("languages.json") {
+            match std::str::from_utf8(f.data.as_ref()) {
+                Ok(string) => match serde_json::from_str(string) {
+                    Ok(defs) => defs,
+                    Err(e) => panic!("json error: {}", e),
+                },
+                Err(e) => panic!("fatal: {}", e),
+            }
+        } else {
+            panic!("fatal: no asset was included!!");
+        }
+    }
+}
+
+impl LanguageDefs {
+    /// new creates a new LanguageDefs object from the given definitions.
+    pub fn new(defs: Vec<LanguageDef>) -> LanguageDefs {
+        LanguageDefs { defs }
+    }
+
+    /// parse parses the language definitions from the given file.
+    pub fn parse(path: PathBuf) -> Result<LanguageDefs> {
+        match OpenOptions::new().read(true).open(path) {
+            Ok(file) => match serde_json::from_reader(BufReader::new(file)) {
+                Ok(defs) => Ok(defs),
+                Err(e) => Err(MeisterError::Json(e)),
+            },
+            Err(e) => Err(MeisterError::IO(e)),
+        }
+    }
+
+    /// len returns the number of the language definitions.
+    pub fn len(&self) -> usize {
+        self.defs.len()
+    }
+
+    /// is_empty returns true if the language definitions are empty.
+    pub fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
+
+    /// iter returns an iterator of the language definitions.
+    pub fn iter(&self) -> impl Iterator<Item = &LanguageDef> + '_ {
+        self.defs.iter()
+    }
+
+    /// append appends the language definitions of the other object to this object.
+    pub fn append(&mut self, other: &mut LanguageDefs) {
+        self.defs.append(&mut other.defs);
+    }
+}
+
+/// Census maps the extensions of the files to the index of the languages.
+pub(crate) struct Census {
+    names: Vec<String>,
+    table: HashMap<String, usize>,
+}
+
+impl Census {
+    pub(crate) fn new(defs: &LanguageDefs) -> Self {
+        let mut table = HashMap::new();
+        for (index, def) in defs.iter().enumerate() {
+            for ext in def.extensions.iter() {
+                table.entry(ext.to_lowercase()).or_insert(index);
+            }
+        }
+        Census {
+            names: defs.iter().map(|d| d.name.clone()).collect(),
+            table,
+        }
+    }
+
+    /// counter returns the empty counts of the languages.
+    pub(crate) fn counter(&self) -> Vec<usize> {
+        vec![0; self.names.len()]
+    }
+
+    /// count increments the count of the language of the given file.
+    pub(crate) fn count(&self, path: &Path, counts: &mut [usize]) {
+        if let Some(index) = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|e| self.table.get(&e.to_lowercase()))
+        {
+            counts[*index] += 1;
+        }
+    }
+
+    /// result converts the counts into the list of [LanguageCount] in descending order of the number of the files.
+    pub(crate) fn result(&self, counts: &[usize]) -> Vec<LanguageCount> {
+        let mut result = self
+            .names
+            .iter()
+            .zip(counts.iter())
+            .filter(|(_, count)| **count > 0)
+            .map(|(name, count)| LanguageCount {
+                name: name.clone(),
+                files: *count,
+            })
+            .collect::<Vec<LanguageCount>>();
+        result.sort_by(|a, b| b.files.cmp(&a.files).then_with(|| a.name.cmp(&b.name)));
+        result
+    }
+}
+
+/// The `construct` function creates a LanguageDefs object from the given definition file and append file,
+/// in the same manner as [construct](crate::defs::construct) of the build tool definitions.
+pub fn construct(defs: Option<PathBuf>, append: Option<PathBuf>) -> Result<LanguageDefs> {
+    let mut def = match defs {
+        Some(path) => LanguageDefs::parse(path)?,
+        None => LanguageDefs::default(),
+    };
+    if let Some(path) = append {
+        let mut additional_defs = LanguageDefs::parse(path)?;
+        def.append(&mut additional_defs);
+    }
+    Ok(def)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default() {
+        let defs = LanguageDefs::default();
+        assert!(!defs.is_empty());
+        assert!(defs.iter().any(|d| d.name == "Rust"));
+    }
+
+    #[test]
+    fn test_census() {
+        let census = Census::new(&LanguageDefs::new(vec![
+            LanguageDef {
+                name: "Java".to_string(),
+                extensions: vec!["java".to_string()],
+            },
+            LanguageDef {
+                name: "Rust".to_string(),
+                extensions: vec!["rs".to_string()],
+            },
+        ]));
+        let mut counts = census.counter();
+        for file in [
+            "src/Main.java",
+            "lib.rs",
+            "main.RS",
+            "README",
+            "build.gradle",
+        ] {
+            census.count(Path::new(file), &mut counts);
+        }
+        assert_eq!(
+            vec![
+                LanguageCount {
+                    name: "Rust".to_string(),
+                    files: 2
+                },
+                LanguageCount {
+                    name: "Java".to_string(),
+                    files: 1
+                },
+            ],
+            census.result(&counts)
+        );
+    }
+}
@@ -8,8 +8,21 @@
  * Next, build an object of [Meister] with the definitions and
  * directory traversing options (`its`: ignore types).
  * If the its is empty vector, the default value [IgnoreType::Default] will be used.
+ * The ignore types are applied to the entries of the archive files as well,
+ * respecting the `.gitignore` and `.ignore` files in the archive files.
+ * [Meister::find_projects] reports each project root in the archive files separately.
  *
  * Finally, detect the build tools in the specified directory and print the result.
+ * For large directories, [Meister::find_iter] and [Meister::find_with] report
+ * the detected files one by one during the traversal.
+ * The revision of a git repository is also detectable without checking it out,
+ * by giving the path in the form of `path/to/repo.git@v1.2.0` (see [parse_revision]).
+ * Moreover, [Meister::history] reports when each build tool appeared and disappeared in the commits (see [history]).
+ * The differences between two results are reported by [diff::diff].
+ * The results are checked against the rules of the organization by [policy::Policy::check].
+ * For the repeated detections in large directories, [Meister::with_cache] skips reading the unchanged directories.
+ * With the `watch` feature, [Meister::watch] reports the changes of the build tools in a directory as they happen.
+ * Also, [Meister::with_languages] enables the language census of the projects (see [langs]).
  *
  * ```
  * // The first step
@@ -33,20 +46,40 @@
  * }
  *  ```
  */
+mod cache;
 pub mod defs;
+pub mod diff;
 mod extractors;
+mod git;
+pub mod history;
+mod ignores;
+pub mod langs;
+pub mod policy;
+mod rank;
+mod roots;
+mod tasks;
+mod versions;
+#[cfg(feature = "watch")]
+pub mod watch;
 
 use clap::ValueEnum;
 use path_matchers::{glob, PathMatcher};
 use serde_json::Error as JsonError;
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use defs::{BuildToolDef, BuildToolDefs};
+pub use extractors::ArchiveLimits;
+pub use git::parse_revision;
+use langs::{Census, LanguageCount, LanguageDefs};
 
 /// MeisterError represents an error of the project.
 #[derive(Debug)]
 pub enum MeisterError {
+    /// the archive file is corrupt, or exceeds the [ArchiveLimits].
+    Archive(String),
     /// arrays of [MeisterError].
     Array(Vec<MeisterError>),
     /// Fatal error.
@@ -55,14 +88,20 @@ pub enum MeisterError {
     IO(std::io::Error),
     /// JSON error.
     Json(JsonError),
+    /// error of the git command.
+    Git(String),
     /// NotImplemented error.
     NotImplemented,
+    /// the number of the projects violating the policy.
+    PolicyViolation(usize),
     /// specified directories or files is not a project.
     NotProject(String),
     /// if no project was specified.
     NoProjectSpecified(),
     /// The given project does not exist.
     ProjectNotFound(PathBuf),
+    /// the given build tool name is not defined, with the closest name of the definitions, if any.
+    UnknownTool(String, Option<String>),
     /// the given archive file was not supported.
     UnsupportedArchiveFormat(String),
     /// warning message.
@@ -131,7 +170,7 @@ impl Display for IgnoreType {
 
 pub fn is_supported_archive_format<P: AsRef<Path>>(arg: P) -> bool {
     let arg = arg.as_ref();
-    let name = arg.to_str().unwrap().to_lowercase();
+    let name = arg.to_string_lossy().to_lowercase();
     for (_, ext) in extractors::exts().iter() {
         if name.ends_with(ext) {
             return true;
@@ -151,6 +190,9 @@ pub struct BuildTools {
     pub base: PathBuf,
     /// The detected files of build tools.
     pub tools: Vec<BuildTool>,
+    /// The number of the files of each language in the project, in descending order of the number.
+    /// This field is filled only when the language census of [Meister] is enabled.
+    pub languages: Vec<LanguageCount>,
 }
 
 /// BuildTool represents a detected file for build tool.
@@ -160,13 +202,110 @@ pub struct BuildTool {
     pub path: PathBuf,
     /// the build tool definition corresponding to the detected file.
     pub def: BuildToolDef,
+    /// the other build tool definitions matched to the detected file.
+    /// This field is filled only when the multi-match mode of [Meister] is enabled.
+    pub alternatives: Vec<BuildToolDef>,
+    /// the pinned version of the build tool, e.g., `distributionUrl` of the Gradle wrapper,
+    /// `rust-toolchain.toml`, `cmake_minimum_required`, and `packageManager` of `package.json`.
+    /// This field is filled only when the version detection of [Meister] is enabled.
+    pub version: Option<String>,
+    /// true if the wrapper script (e.g., `gradlew` or `mvnw`) exists beside the detected file.
+    /// This field is filled only when the version detection of [Meister] is enabled.
+    pub wrapper: bool,
+    /// the tasks (entry points) defined in the detected file, e.g., the targets of `Makefile`.
+    /// This field is filled only when the task enumeration of [Meister] is enabled.
+    pub tasks: Vec<String>,
+    /// the confidence that the build tool of this file is the primary one of the project (0.0 to 1.0).
+    /// The confidences are calculated by [Meister::find], and are 0.0 for the streaming APIs.
+    pub confidence: f64,
 }
 
-trait Matcher {
+impl BuildTool {
+    /// new creates a BuildTool object with the given path and definition, without alternatives.
+    pub fn new(path: PathBuf, def: BuildToolDef) -> Self {
+        BuildTool {
+            path,
+            def,
+            alternatives: vec![],
+            version: None,
+            wrapper: false,
+            tasks: vec![],
+            confidence: 0.0,
+        }
+    }
+}
+
+/// ToolFilter selects the build tool definitions used by [Meister] by their names.
+/// The names are compared with the names of [BuildToolDef] ignoring the case.
+#[derive(Clone, Debug, Default)]
+pub struct ToolFilter {
+    /// the names of the build tools to detect. Empty means all of the build tools.
+    pub only: Vec<String>,
+    /// the names of the build tools to ignore.
+    pub excludes: Vec<String>,
+}
+
+impl ToolFilter {
+    /// new creates a ToolFilter object with the given names to detect and to ignore.
+    pub fn new(only: Vec<String>, excludes: Vec<String>) -> Self {
+        ToolFilter { only, excludes }
+    }
+
+    /// accepts returns true if the build tool of the given name passes this filter.
+    pub fn accepts(&self, name: &str) -> bool {
+        (self.only.is_empty() || self.only.iter().any(|n| n.eq_ignore_ascii_case(name)))
+            && !self.excludes.iter().any(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    /// validate checks that all of the names in this filter are defined in the given definitions.
+    /// The names should be validated against the whole definitions before filtering them by the categories
+    /// (see [BuildToolDefs::retain_categories]), since the names of the removed definitions are still valid.
+    pub fn validate(&self, defs: &BuildToolDefs) -> Result<()> {
+        validate_names(self.only.iter().chain(self.excludes.iter()), defs)
+    }
+}
+
+/// validate_names returns [MeisterError::UnknownTool] errors for the given names not in the definitions,
+/// with the closest names of the definitions.
+pub(crate) fn validate_names<'a, I>(names: I, defs: &BuildToolDefs) -> Result<()>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let errs = names
+        .filter(|name| !defs.iter().any(|d| d.name.eq_ignore_ascii_case(name)))
+        .map(|name| MeisterError::UnknownTool(name.clone(), closest_name(name, defs)))
+        .collect::<Vec<MeisterError>>();
+    if errs.is_empty() {
+        Ok(())
+    } else {
+        Err(MeisterError::Array(errs))
+    }
+}
+
+/// closest_name returns the name of the definition most similar to the given name.
+pub(crate) fn closest_name(name: &str, defs: &BuildToolDefs) -> Option<String> {
+    let name = name.to_lowercase();
+    defs.iter()
+        .map(|d| (strsim::levenshtein(&name, &d.name.to_lowercase()), &d.name))
+        .filter(|(distance, _)| *distance <= name.len().max(3) / 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, n)| n.clone())
+}
+
+trait Matcher: Send + Sync {
     fn matches(&self, p: &Path) -> bool;
 }
 
 impl BuildTools {
+    /// new creates a BuildTools object with the given base and detected files, without the language census.
+    pub fn new(base: PathBuf, tools: Vec<BuildTool>) -> Self {
+        BuildTools {
+            base,
+            tools,
+            languages: vec![],
+        }
+    }
+
     /// path_of returns the relative path of the detected file from the project path.
     pub fn path_of(&self, index: usize) -> Result<String> {
         if let Some(bt) = self.tools.get(index) {
@@ -179,16 +318,48 @@ impl BuildTools {
             Err(MeisterError::Fatal(format!("index {} out of range", index)))
         }
     }
+
+    /// primary returns the definition of the primary build tool of the project,
+    /// that is, the build tool with the highest confidence.
+    pub fn primary(&self) -> Option<&BuildToolDef> {
+        self.tools
+            .iter()
+            .filter(|bt| bt.confidence > 0.0)
+            .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+            .map(|bt| &bt.def)
+    }
+
+    /// retain_primary removes the detected files other than the ones of the primary build tool.
+    pub fn retain_primary(&mut self) {
+        if let Some(name) = self.primary().map(|d| d.name.clone()) {
+            self.tools.retain(|bt| bt.def.name == name);
+        } else {
+            self.tools.clear();
+        }
+    }
 }
 
 /// Meister is a object for detecting the build tools in the specified directory.
 /// This object contains the definitions of the build tools.
 /// In use of user own build tool definitions, use `Meister::new` method for building the object.
+/// Cloning this object is cheap, since the compiled matchers are shared among the clones.
+#[derive(Clone)]
 pub struct Meister {
     defs: Vec<BuildToolDef>,
-    excludes: MultipleMatcher,
-    matchers: Vec<MultipleMatcher>,
+    excludes: Arc<ExcludeMatcher>,
+    matchers: Arc<Vec<MultipleMatcher>>,
+    contents: Arc<Vec<Option<ContentMatcher>>>,
+    enabled: Arc<Vec<bool>>,
+    census: Option<Arc<Census>>,
+    cache: Option<PathBuf>,
+    archive_depth: usize,
+    archive_limits: ArchiveLimits,
+    open_archives: bool,
     its: Vec<IgnoreType>,
+    threads: usize,
+    multi_match: bool,
+    tasks: bool,
+    versions: bool,
 }
 
 impl Default for Meister {
@@ -208,6 +379,12 @@ impl Meister {
 
     /// creates a instance of Meister object with the specified build tool definitions, ignore types, and exclude patterns.
     /// If `its` was the empty, the default value ([IgnoreType::Default]) will be used.
+    ///
+    /// The exclude patterns are the gitignore style globs evaluated relative to the project base,
+    /// e.g., `/test` excludes only `test` on the top of the project, `*.md` excludes the markdown files in any directory,
+    /// and `!keep.md` re-includes the file excluded by the preceding patterns.
+    /// The patterns starting with `re:` are regular expressions matched against the relative paths.
+    /// The exclude patterns are applied to both of the directories and the entries of the archive files.
     pub fn new_with_excludes(
         defs: BuildToolDefs,
         its: Vec<IgnoreType>,
@@ -218,100 +395,601 @@ impl Meister {
         } else {
             its
         };
+        let contents = build_content_matchers(&defs.defs)?;
+        let excludes = ExcludeMatcher::new(excludes)?;
         match build_matchers(defs.defs.clone()) {
             Ok(m) => Ok(Self {
                 defs: defs.defs.clone(),
-                excludes: build_exclude_matchers(excludes),
-                matchers: m,
+                excludes: Arc::new(excludes),
+                matchers: Arc::new(m),
+                contents: Arc::new(contents),
+                enabled: Arc::new(vec![true; defs.defs.len()]),
+                census: None,
+                cache: None,
+                archive_depth: 0,
+                archive_limits: ArchiveLimits::default(),
+                open_archives: false,
                 its: its2,
+                threads: 1,
+                multi_match: false,
+                tasks: false,
+                versions: false,
             }),
             Err(e) => Err(e),
         }
     }
 
-    /// find detects the build tools in the specified directory.
+    /// with_threads sets the number of threads for traversing the directories.
+    /// If `threads` is 1, the directories are traversed in the current thread,
+    /// and if `threads` is 0, the number of threads is chosen automatically.
+    /// In both cases, the detected files are returned in the same order.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// with_multi_match enables or disables the multi-match mode.
+    /// In the multi-match mode, the detected file is matched against all of the definitions,
+    /// and the definitions other than the first matched one are stored in [BuildTool::alternatives].
+    pub fn with_multi_match(mut self, multi_match: bool) -> Self {
+        self.multi_match = multi_match;
+        self
+    }
+
+    /// with_tasks enables or disables the task enumeration.
+    /// If enabled, the tasks defined in the detected files of the task runners
+    /// (`Makefile`, `Justfile`, `Taskfile.yml`, and `package.json`) are stored in [BuildTool::tasks].
+    pub fn with_tasks(mut self, tasks: bool) -> Self {
+        self.tasks = tasks;
+        self
+    }
+
+    /// with_versions enables or disables the detection of the pinned versions and the wrapper scripts.
+    /// If enabled, the versions pinned beside the detected files (e.g., `gradle/wrapper/gradle-wrapper.properties`)
+    /// are stored in [BuildTool::version], and the presence of the wrapper scripts in [BuildTool::wrapper].
+    pub fn with_versions(mut self, versions: bool) -> Self {
+        self.versions = versions;
+        self
+    }
+
+    /// with_archive_depth sets the maximum depth for descending into the archives nested in the archive file,
+    /// e.g., the jar files in a tar.gz file (default: 0, no descent).
+    /// The entries in the nested archives are reported with the path of the nested archive and `!/`,
+    /// e.g., `lib/app.jar!/pom.xml` in `dist.tar.gz`.
+    pub fn with_archive_depth(mut self, depth: usize) -> Self {
+        self.archive_depth = depth;
+        self
+    }
+
+    /// with_archive_limits sets the limits for reading the archive files (default: [ArchiveLimits::default]).
+    /// The archive files exceeding the limits are reported as [MeisterError::Archive].
+    pub fn with_archive_limits(mut self, limits: ArchiveLimits) -> Self {
+        self.archive_limits = limits;
+        self
+    }
+
+    /// with_open_archives enables or disables the detection in the archive files found in the directories.
+    /// If enabled, the entries of the archive files (see [is_supported_archive_format]) are also detected,
+    /// and reported under the path of the archive file, e.g., `vendor/app.jar!/pom.xml`.
+    /// The archive files failed to read are skipped with the warnings in the log.
+    pub fn with_open_archives(mut self, open_archives: bool) -> Self {
+        self.open_archives = open_archives;
+        self
+    }
+
+    /// with_tool_filter restricts the build tools to detect by their names.
+    /// The names are not validated by this method, since the definitions of [Meister] may be filtered by the categories.
+    /// Use [ToolFilter::validate] with the whole definitions to report the unknown names.
+    pub fn with_tool_filter(mut self, filter: ToolFilter) -> Result<Self> {
+        self.enabled = Arc::new(self.defs.iter().map(|d| filter.accepts(&d.name)).collect());
+        Ok(self)
+    }
+
+    /// with_languages enables the language census with the given language definitions.
+    /// The census counts the files of each language in the project, and stores them in [BuildTools::languages].
+    pub fn with_languages(mut self, langs: LanguageDefs) -> Self {
+        self.census = Some(Arc::new(Census::new(&langs)));
+        self
+    }
+
+    /// find detects the build tools in the specified directory, archive file, or revision of git repository.
     pub fn find(&self, base: PathBuf) -> Result<BuildTools> {
-        if base.is_file() {
+        if let (Some(cache), true) = (&self.cache, base.is_dir()) {
+            return self.find_directory_cached(base, cache);
+        }
+        if base.is_dir() && self.threads != 1 {
+            return self.find_directory_parallel(base);
+        }
+        let mut result = vec![];
+        let mut errs = vec![];
+        let mut iter = self.find_iter(base.clone())?;
+        for item in iter.by_ref() {
+            match item {
+                Ok(bt) => result.push(bt),
+                Err(e) => errs.push(e),
+            }
+        }
+        let languages = iter.languages();
+        build_result(base, result, errs, languages)
+    }
+
+    /// find_async detects the build tools in the specified directory or archive file
+    /// without blocking the asynchronous runtime.
+    /// The traversal and the extraction run on the blocking thread pool of tokio,
+    /// therefore, this function must be called within a tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn find_async(&self, base: PathBuf) -> Result<BuildTools> {
+        let meister = self.clone();
+        match tokio::task::spawn_blocking(move || meister.find(base)).await {
+            Ok(r) => r,
+            Err(e) => Err(MeisterError::Fatal(format!("async task: {}", e))),
+        }
+    }
+
+    /// find_iter returns an iterator which yields the build tools in the specified directory or archive file
+    /// as they are detected.
+    /// The warnings while walking the directory are yielded as `Err(MeisterError::Warning)`,
+    /// and the iteration goes on after them.
+    /// The directories are always traversed in the current thread, regardless of [Meister::with_threads].
+    pub fn find_iter(&self, base: PathBuf) -> Result<BuildToolIter<'_>> {
+        let revision = if base.exists() {
+            None
+        } else {
+            parse_revision(&base)
+        };
+        let mut contents = HashMap::new();
+        let entries = if let Some((repo, rev)) = &revision {
+            Entries::Archive(git::list_entries(repo, rev)?.into_iter())
+        } else if base.is_file() {
             if is_supported_archive_format(&base) {
-                self.find_archive(base)
+                let listing = self.list_archive(&base)?;
+                contents = listing.contents;
+                Entries::Archive(listing.entries.into_iter())
             } else {
-                Err(MeisterError::UnsupportedArchiveFormat(
+                return Err(MeisterError::UnsupportedArchiveFormat(
                     base.display().to_string(),
-                ))
+                ));
             }
         } else {
-            self.find_directory(base)
+            Entries::Directory(Box::new(self.walker(base.clone()).build()))
+        };
+        Ok(BuildToolIter {
+            meister: self,
+            base,
+            entries,
+            revision: revision.map(|(repo, rev)| (git::Batch::new(&repo), rev)),
+            contents,
+            counts: self.census.as_ref().map(|c| c.counter()),
+            pending: std::collections::VecDeque::new(),
+        })
+    }
+
+    /// find_with calls the given callback with each build tool in the specified directory or archive file
+    /// as it is detected.
+    /// The warnings while walking the directory are also passed to the callback as `Err(MeisterError::Warning)`.
+    /// If the callback returns `false`, the detection stops immediately.
+    pub fn find_with<F>(&self, base: PathBuf, mut callback: F) -> Result<()>
+    where
+        F: FnMut(Result<BuildTool>) -> bool,
+    {
+        for item in self.find_iter(base)? {
+            if !callback(item) {
+                break;
+            }
         }
+        Ok(())
     }
 
-    fn find_archive(&self, base: PathBuf) -> Result<BuildTools> {
-        let mut tools = vec![];
-        match extractors::list_entries(base.clone()) {
-            Err(e) => Err(e),
-            Ok(entries) => {
-                for entry in entries {
-                    if let Some(bt) = find_build_tool(self, &PathBuf::from(entry)) {
-                        tools.push(bt);
+    fn find_directory_parallel(&self, base: PathBuf) -> Result<BuildTools> {
+        let result = Mutex::new(vec![]);
+        let errs = Mutex::new(vec![]);
+        let counts = Mutex::new(self.census.as_ref().map(|c| c.counter()));
+        let walker = self
+            .walker(base.clone())
+            .threads(self.threads)
+            .build_parallel();
+        walker.run(|| {
+            Box::new(|entry| {
+                match entry {
+                    Ok(entry) => {
+                        if let (Some(census), true) = (&self.census, is_file(&entry)) {
+                            if let Some(counts) = counts.lock().unwrap().as_mut() {
+                                census.count(entry.path(), counts);
+                            }
+                        }
+                        if let Some(bt) = self.find_entry(&base, entry.path()) {
+                            result.lock().unwrap().push(bt);
+                        }
+                        if self.opens_archive(&entry) {
+                            // the archive is counted locally, not to block the other threads while scanning it.
+                            let mut local = self.census.as_ref().map(|c| c.counter());
+                            let tools = self.find_in_archive(entry.path(), local.as_deref_mut());
+                            if let (Some(counts), Some(local)) =
+                                (counts.lock().unwrap().as_mut(), local)
+                            {
+                                counts.iter_mut().zip(local).for_each(|(c, l)| *c += l);
+                            }
+                            result.lock().unwrap().extend(tools);
+                        }
                     }
+                    Err(e) => errs
+                        .lock()
+                        .unwrap()
+                        .push(MeisterError::Warning(format!("walking: {}", e))),
                 }
-                Ok(BuildTools { base, tools })
-            }
+                ignore::WalkState::Continue
+            })
+        });
+        let mut result = result.into_inner().unwrap();
+        result.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut errs = errs.into_inner().unwrap();
+        errs.sort_by_key(|e| format!("{:?}", e));
+        let languages = match (&self.census, counts.into_inner().unwrap()) {
+            (Some(census), Some(counts)) => census.result(&counts),
+            _ => vec![],
+        };
+        build_result(base, result, errs, languages)
+    }
+
+    /// walker builds the directory walker which skips the subtrees matched to the exclude patterns.
+    fn walker(&self, base: PathBuf) -> ignore::WalkBuilder {
+        self.walker_at(base.clone(), &base)
+    }
+
+    /// walker_at builds the directory walker for the subtree `root` of `base`,
+    /// which skips the subtrees matched to the exclude patterns relative to `base`.
+    fn walker_at(&self, base: PathBuf, root: &Path) -> ignore::WalkBuilder {
+        let mut builder = build_walker(root.to_path_buf(), &self.its);
+        if !self.excludes.is_empty() {
+            let excludes = Arc::clone(&self.excludes);
+            builder.filter_entry(move |entry| {
+                let target = entry.path().strip_prefix(&base).unwrap_or(entry.path());
+                target.as_os_str().is_empty() || !excludes.matches(target)
+            });
         }
+        builder
+    }
+
+    /// opens_archive returns true if the given entry of the directory is an archive file to detect in.
+    fn opens_archive(&self, entry: &ignore::DirEntry) -> bool {
+        self.open_archives && is_file(entry) && is_supported_archive_format(entry.path())
+    }
+
+    /// list_archive returns the entries of the archive file, except the entries ignored by the ignore types,
+    /// with the contents of the entries read for the detection and the ignore files (see [Meister::wants]).
+    /// The ignore files in the archive file are respected in the same manner as the directories (see [build_walker]).
+    fn list_archive(&self, archive: &Path) -> Result<extractors::Listing> {
+        let mut listing = extractors::list_nested_contents(
+            archive.to_path_buf(),
+            self.archive_depth,
+            &self.archive_limits,
+            &|entry| self.wants(entry),
+        )?;
+        let its = normalize_ignore_types(&self.its);
+        let ignore = ignores::ArchiveIgnore::new(&listing.entries, &its, |name| {
+            listing.contents.get(name).cloned()
+        });
+        listing.entries.retain(|entry| !ignore.is_ignored(entry));
+        Ok(listing)
     }
 
-    fn find_directory(&self, base: PathBuf) -> Result<BuildTools> {
+    /// wants returns true if the content of the given entry of the archive file may be read for the detection,
+    /// that is, the entry matches the definitions needing the content, is read for the versions, or is an ignore file.
+    /// Since the path patterns are also matched relative to the project roots (see [Meister::find_projects]),
+    /// every tail of the entry path is tested.
+    fn wants(&self, entry: &str) -> bool {
+        if ignores::is_ignore_file(entry) {
+            return true;
+        }
+        let path = Path::new(entry);
+        if self.versions && path.file_name().is_some_and(versions::is_probed) {
+            return true;
+        }
+        let components = path.components().collect::<Vec<_>>();
+        let reads = |(matcher, contents): (&MultipleMatcher, &Option<ContentMatcher>)| {
+            (self.tasks || self.versions || contents.is_some())
+                && (0..components.len())
+                    .any(|i| matcher.matches(&components[i..].iter().collect::<PathBuf>()))
+        };
+        self.matchers
+            .iter()
+            .zip(self.contents.iter())
+            .zip(self.enabled.iter())
+            .any(|(pair, enabled)| *enabled && reads(pair))
+    }
+
+    /// find_in_archive detects the build tools in the archive file found in the directory,
+    /// and returns them with the paths under the archive file.
+    fn find_in_archive(&self, archive: &Path, mut counts: Option<&mut [usize]>) -> Vec<BuildTool> {
+        let listing = match self.list_archive(archive) {
+            Ok(listing) => listing,
+            Err(e) => {
+                log::warn!("{}: listing entries: {:?}", archive.display(), e);
+                return vec![];
+            }
+        };
+        let source = Source::Archive(&listing.contents);
         let mut result = vec![];
-        let mut errs = vec![];
-        let walker = build_walker(base.clone(), &self.its);
-        for entry in walker {
-            match entry {
-                Ok(entry) => {
-                    let target_path = entry.path();
-                    let target = match target_path.strip_prefix(&base) {
-                        Ok(p) => p,
-                        Err(_) => target_path,
-                    };
-                    log::debug!(
-                        "excludes: {:?} {} ({})",
-                        target,
-                        self.excludes.matches(target),
-                        self.excludes.matchers.len()
-                    );
-                    if !self.excludes.matches(target) {
-                        if let Some(bt) = find_build_tool(self, target) {
-                            result.push(bt);
+        for entry in &listing.entries {
+            let path = PathBuf::from(entry);
+            if self.excludes.matches(&path) {
+                continue;
+            }
+            if let (Some(census), Some(counts), false) =
+                (&self.census, counts.as_deref_mut(), entry.ends_with('/'))
+            {
+                census.count(&path, counts);
+            }
+            if let Some(mut bt) = find_build_tool(self, &path, &source) {
+                bt.path = PathBuf::from(format!(
+                    "{}{}{}",
+                    archive.display(),
+                    extractors::SEPARATOR,
+                    entry
+                ));
+                result.push(bt);
+            }
+        }
+        result
+    }
+
+    fn find_entry(&self, base: &Path, target_path: &Path) -> Option<BuildTool> {
+        let target = match target_path.strip_prefix(base) {
+            Ok(p) => p,
+            Err(_) => target_path,
+        };
+        log::debug!("excludes: {:?} {}", target, self.excludes.matches(target));
+        if self.excludes.matches(target) {
+            None
+        } else {
+            find_build_tool(self, target, &Source::Directory(base))
+        }
+    }
+}
+
+/// BuildToolIter is an iterator over the detected build tools, created by [Meister::find_iter].
+pub struct BuildToolIter<'a> {
+    meister: &'a Meister,
+    base: PathBuf,
+    entries: Entries,
+    revision: Option<(git::Batch, String)>,
+    /// the contents of the entries read while listing the archive file.
+    contents: HashMap<String, Vec<u8>>,
+    counts: Option<Vec<usize>>,
+    /// the build tools detected in the archive file found in the directory, not yielded yet.
+    pending: std::collections::VecDeque<BuildTool>,
+}
+
+enum Entries {
+    Directory(Box<ignore::Walk>),
+    /// the entries of the archive file or the tree of the git revision.
+    Archive(std::vec::IntoIter<String>),
+}
+
+impl BuildToolIter<'_> {
+    /// base returns the project directory or archive file of this iterator.
+    pub fn base(&self) -> &Path {
+        &self.base
+    }
+
+    /// languages returns the language census of the entries traversed so far.
+    /// The result is empty unless the language census of [Meister] is enabled.
+    pub fn languages(&self) -> Vec<LanguageCount> {
+        match (&self.meister.census, &self.counts) {
+            (Some(census), Some(counts)) => census.result(counts),
+            _ => vec![],
+        }
+    }
+
+    fn count(&mut self, path: &Path) {
+        if let (Some(census), Some(counts)) = (&self.meister.census, self.counts.as_mut()) {
+            census.count(path, counts);
+        }
+    }
+}
+
+fn is_file(entry: &ignore::DirEntry) -> bool {
+    entry.file_type().is_some_and(|t| t.is_file())
+}
+
+impl Iterator for BuildToolIter<'_> {
+    type Item = Result<BuildTool>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(bt) = self.pending.pop_front() {
+                return Some(Ok(bt));
+            }
+            let found = match &mut self.entries {
+                Entries::Directory(walker) => match walker.next()? {
+                    Ok(entry) => {
+                        if is_file(&entry) {
+                            self.count(entry.path());
+                        }
+                        if self.meister.opens_archive(&entry) {
+                            let tools = self
+                                .meister
+                                .find_in_archive(entry.path(), self.counts.as_deref_mut());
+                            self.pending.extend(tools);
+                        }
+                        self.meister.find_entry(&self.base, entry.path())
+                    }
+                    Err(e) => {
+                        return Some(Err(MeisterError::Warning(format!("walking: {}", e))));
+                    }
+                },
+                Entries::Archive(entries) => {
+                    let entry = PathBuf::from(entries.next()?);
+                    if self.meister.excludes.matches(&entry) {
+                        None
+                    } else {
+                        if !entry.to_string_lossy().ends_with('/') {
+                            self.count(&entry);
                         }
+                        let source = match &self.revision {
+                            Some((batch, rev)) => Source::Revision(batch, rev),
+                            None => Source::Archive(&self.contents),
+                        };
+                        find_build_tool(self.meister, &entry, &source)
                     }
                 }
-                Err(e) => errs.push(MeisterError::Warning(format!("walking: {}", e))),
+            };
+            if let Some(bt) = found {
+                return Some(Ok(bt));
             }
         }
-        if errs.is_empty() {
-            Ok(BuildTools {
-                base,
-                tools: result,
-            })
-        } else {
-            Err(MeisterError::Array(errs))
+    }
+}
+
+fn build_result(
+    base: PathBuf,
+    tools: Vec<BuildTool>,
+    errs: Vec<MeisterError>,
+    languages: Vec<LanguageCount>,
+) -> Result<BuildTools> {
+    if errs.is_empty() {
+        let mut result = BuildTools {
+            base,
+            tools,
+            languages,
+        };
+        rank::rank(&mut result);
+        Ok(result)
+    } else {
+        Err(MeisterError::Array(errs))
+    }
+}
+
+/// Source represents the location for reading the contents of the detected files.
+enum Source<'a> {
+    /// the files are read from the base directory.
+    Directory(&'a Path),
+    /// the files are read from the base directory, and the paths of them are recorded (for the cache).
+    Recorded(&'a Path, &'a std::cell::RefCell<Vec<PathBuf>>),
+    /// the files are read from the contents of the entries read while listing the archive file
+    /// (see [Meister::list_archive]). The entries not read while listing are treated as missing.
+    Archive(&'a HashMap<String, Vec<u8>>),
+    /// the files are read from the tree of the revision (the second) of the git repository (the first).
+    Revision(&'a git::Batch, &'a str),
+}
+
+impl Source<'_> {
+    fn read(&self, path: &Path) -> Option<String> {
+        let bytes = match self {
+            Source::Directory(base) => std::fs::read(base.join(path)).ok(),
+            Source::Recorded(base, paths) => {
+                paths.borrow_mut().push(path.to_path_buf());
+                std::fs::read(base.join(path)).ok()
+            }
+            Source::Archive(contents) => contents.get(path.to_string_lossy().as_ref()).cloned(),
+            Source::Revision(batch, rev) => match batch.read_entry(rev, &path.to_string_lossy()) {
+                Ok(r) => r,
+                Err(e) => {
+                    log::warn!(
+                        "{}@{}: reading {}: {:?}",
+                        batch.repo().display(),
+                        rev,
+                        path.display(),
+                        e
+                    );
+                    None
+                }
+            },
+        };
+        bytes.map(|b| String::from_utf8_lossy(&b).to_string())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        match self {
+            Source::Directory(base) => base.join(path).is_file(),
+            Source::Recorded(base, paths) => {
+                paths.borrow_mut().push(path.to_path_buf());
+                base.join(path).is_file()
+            }
+            Source::Archive(contents) => contents.contains_key(path.to_string_lossy().as_ref()),
+            Source::Revision(_, _) => self.read(path).is_some(),
         }
     }
 }
 
-fn find_build_tool(meister: &Meister, path: &Path) -> Option<BuildTool> {
+fn find_build_tool(meister: &Meister, path: &Path, source: &Source) -> Option<BuildTool> {
+    find_build_tool_as(meister, path, path, source)
+}
+
+/// find_build_tool_as detects the build tool of the file at `path` in the source,
+/// by matching the definitions against `matched` (e.g., the path relative to the project root).
+fn find_build_tool_as(
+    meister: &Meister,
+    path: &Path,
+    matched: &Path,
+    source: &Source,
+) -> Option<BuildTool> {
     log::trace!("find_build_tool: {}", path.display());
-    for (def, matcher) in meister.defs.iter().zip(meister.matchers.iter()) {
-        let pb = path.to_path_buf();
-        if matcher.matches(&pb) {
-            return Some(BuildTool {
-                path: pb,
-                def: def.clone(),
-            });
+    let mut content: Option<Option<String>> = None;
+    let mut result = match_build_tool(meister, path, matched, source, &mut content);
+    if let Some(bt) = result.as_mut() {
+        if meister.versions {
+            (bt.version, bt.wrapper) = versions::detect(path, source);
+        }
+        if meister.tasks {
+            if let Some(content) = content.get_or_insert_with(|| source.read(path)) {
+                bt.tasks = tasks::list(path, content);
+            }
+        }
+    }
+    result
+}
+
+/// match_build_tool returns the build tool matched to the file at `path` by the definitions,
+/// without the versions, the wrappers, and the tasks.
+/// The content of the file is read into `content` only if the definitions have the content patterns.
+/// The definition whose content patterns matched precedes the definitions matched only by the file name,
+/// e.g., a `Makefile` generated by automake is reported as automake rather than Make.
+fn match_build_tool(
+    meister: &Meister,
+    path: &Path,
+    matched: &Path,
+    source: &Source,
+    content: &mut Option<Option<String>>,
+) -> Option<BuildTool> {
+    let mut result: Option<BuildTool> = None;
+    let mut by_contents = false;
+    for (((def, matcher), contents), enabled) in meister
+        .defs
+        .iter()
+        .zip(meister.matchers.iter())
+        .zip(meister.contents.iter())
+        .zip(meister.enabled.iter())
+    {
+        if !enabled || !matcher.matches(matched) {
+            continue;
+        }
+        if let Some(cm) = contents {
+            let content = content.get_or_insert_with(|| source.read(path));
+            if !content.as_ref().is_some_and(|c| cm.matches(c)) {
+                continue;
+            }
+        }
+        match result.as_mut() {
+            None => result = Some(BuildTool::new(path.to_path_buf(), def.clone())),
+            Some(bt) if contents.is_some() && !by_contents => {
+                let general = std::mem::replace(&mut bt.def, def.clone());
+                if meister.multi_match {
+                    bt.alternatives.insert(0, general);
+                }
+            }
+            Some(bt) if meister.multi_match => bt.alternatives.push(def.clone()),
+            Some(_) => {}
+        }
+        by_contents |= contents.is_some();
+        if !meister.multi_match && by_contents {
+            break;
         }
     }
-    None
+    result
 }
 
-fn build_walker(base: PathBuf, its: &[IgnoreType]) -> ignore::Walk {
+fn build_walker(base: PathBuf, its: &[IgnoreType]) -> ignore::WalkBuilder {
     let its = normalize_ignore_types(its);
     log::info!(
         "ignore types: {}",
@@ -320,13 +998,15 @@ fn build_walker(base: PathBuf, its: &[IgnoreType]) -> ignore::Walk {
             .collect::<Vec<String>>()
             .join(", ")
     );
-    ignore::WalkBuilder::new(base)
+    let mut builder = ignore::WalkBuilder::new(base);
+    builder
         .hidden(its.contains(&IgnoreType::Hidden))
         .git_ignore(its.contains(&IgnoreType::GitIgnore))
         .git_global(its.contains(&IgnoreType::GitGlobal))
         .git_exclude(its.contains(&IgnoreType::GitExclude))
         .ignore(its.contains(&IgnoreType::Ignore))
-        .build()
+        .sort_by_file_name(|a, b| a.cmp(b));
+    builder
 }
 
 fn normalize_ignore_types(its: &[IgnoreType]) -> Vec<IgnoreType> {
@@ -374,13 +1054,39 @@ fn build_matcher(def: BuildToolDef) -> Result<MultipleMatcher> {
     }
 }
 
-fn build_exclude_matchers(excludes: Vec<String>) -> MultipleMatcher {
-    let matchers = excludes
-        .iter()
-        .map(|e| PartialMatcher { pattern: e.clone() })
-        .map(|m| Box::new(m) as Box<dyn Matcher>)
-        .collect::<Vec<Box<dyn Matcher>>>();
-    MultipleMatcher { matchers }
+fn build_content_matcher(def: &BuildToolDef) -> Result<Option<ContentMatcher>> {
+    if def.contents.is_empty() {
+        return Ok(None);
+    }
+    let mut patterns = vec![];
+    let mut errs = vec![];
+    for content in def.contents.iter() {
+        match ContentPattern::new(content) {
+            Ok(p) => patterns.push(p),
+            Err(e) => errs.push(e),
+        }
+    }
+    if errs.is_empty() {
+        Ok(Some(ContentMatcher { patterns }))
+    } else {
+        Err(MeisterError::Array(errs))
+    }
+}
+
+fn build_content_matchers(defs: &[BuildToolDef]) -> Result<Vec<Option<ContentMatcher>>> {
+    let mut result = vec![];
+    let mut errs = vec![];
+    for def in defs {
+        match build_content_matcher(def) {
+            Ok(m) => result.push(m),
+            Err(e) => errs.push(e),
+        }
+    }
+    if errs.is_empty() {
+        Ok(result)
+    } else {
+        Err(MeisterError::Array(errs))
+    }
 }
 
 fn build_matchers(defs: Vec<BuildToolDef>) -> Result<Vec<MultipleMatcher>> {
@@ -407,11 +1113,49 @@ struct FileNameMatcher {
     name: String,
 }
 struct PathGlobMatcher {
-    pattern: Box<dyn PathMatcher>,
+    pattern: Box<dyn PathMatcher + Send + Sync>,
+}
+
+/// ExcludeMatcher matches the relative paths from the project base with the exclude patterns.
+struct ExcludeMatcher {
+    globs: ignore::gitignore::Gitignore,
+    regexes: Vec<regex::Regex>,
 }
 
-struct PartialMatcher {
-    pattern: String,
+/// ContentMatcher checks the contents of the file whose name was matched by the [MultipleMatcher].
+struct ContentMatcher {
+    patterns: Vec<ContentPattern>,
+}
+
+enum ContentPattern {
+    Literal(String),
+    Regex(regex::Regex),
+}
+
+impl ContentMatcher {
+    fn matches(&self, content: &str) -> bool {
+        self.patterns.iter().any(|p| p.matches(content))
+    }
+}
+
+impl ContentPattern {
+    fn new(pattern: &str) -> Result<Self> {
+        if let Some(re) = pattern.strip_prefix("re:") {
+            match regex::Regex::new(re) {
+                Ok(r) => Ok(ContentPattern::Regex(r)),
+                Err(e) => Err(MeisterError::Fatal(format!("{}: {}", pattern, e))),
+            }
+        } else {
+            Ok(ContentPattern::Literal(pattern.to_string()))
+        }
+    }
+
+    fn matches(&self, content: &str) -> bool {
+        match self {
+            ContentPattern::Literal(s) => content.contains(s),
+            ContentPattern::Regex(r) => r.is_match(content),
+        }
+    }
 }
 
 impl Matcher for MultipleMatcher {
@@ -442,19 +1186,54 @@ impl Matcher for PathGlobMatcher {
     }
 }
 
-impl Matcher for PartialMatcher {
+impl Matcher for ExcludeMatcher {
     fn matches(&self, p: &Path) -> bool {
-        if let Some(path) = p.to_str() {
-            log::debug!(
-                "PartialMatcher: {} {}: {}",
-                path,
-                self.pattern,
-                path.contains(&self.pattern)
-            );
-            path.contains(&self.pattern)
-        } else {
-            false
+        let path = p
+            .components()
+            .filter(|c| matches!(c, std::path::Component::Normal(_)))
+            .collect::<PathBuf>();
+        if path.as_os_str().is_empty() {
+            return false;
+        }
+        let is_dir = p.to_string_lossy().ends_with('/');
+        if self
+            .globs
+            .matched_path_or_any_parents(&path, is_dir)
+            .is_ignore()
+        {
+            return true;
         }
+        let name = path.to_string_lossy();
+        self.regexes.iter().any(|r| r.is_match(&name))
+    }
+}
+
+impl ExcludeMatcher {
+    fn new(excludes: Vec<String>) -> Result<Self> {
+        let mut builder = ignore::gitignore::GitignoreBuilder::new("");
+        let mut regexes = vec![];
+        let mut errs = vec![];
+        for exclude in excludes {
+            if let Some(re) = exclude.strip_prefix("re:") {
+                match regex::Regex::new(re) {
+                    Ok(r) => regexes.push(r),
+                    Err(e) => errs.push(MeisterError::Fatal(format!("{}: {}", exclude, e))),
+                }
+            } else if let Err(e) = builder.add_line(None, &exclude) {
+                errs.push(MeisterError::Fatal(format!("{}: {}", exclude, e)));
+            }
+        }
+        if !errs.is_empty() {
+            return Err(MeisterError::Array(errs));
+        }
+        match builder.build() {
+            Ok(globs) => Ok(ExcludeMatcher { globs, regexes }),
+            Err(e) => Err(MeisterError::Fatal(e.to_string())),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.globs.is_empty() && self.regexes.is_empty()
     }
 }
 
@@ -485,18 +1264,299 @@ mod tests {
 
     #[test]
     fn test_build_walker() {
-        let meister = Meister::default();
+        let meister = Meister::default().with_versions(true);
         let r = meister.find(PathBuf::from("../testdata/fibonacci"));
         assert!(r.is_ok());
         if let Ok(r) = r {
             assert_eq!(1, r.tools.len());
             assert_eq!("Gradle", r.tools[0].def.name);
+            assert_eq!(Some("7.4.2".to_string()), r.tools[0].version);
+            assert!(r.tools[0].wrapper);
+            assert_eq!(1.0, r.tools[0].confidence);
+            assert!(r.primary().is_some_and(|d| d.name == "Gradle"));
             if let Ok(p) = r.path_of(0) {
                 assert_eq!("build.gradle".to_string(), p);
             }
         }
     }
 
+    #[test]
+    fn test_parallel_walker() {
+        let sequential = Meister::default()
+            .find(PathBuf::from("../testdata"))
+            .expect("sequential walk failed");
+        let parallel = Meister::default()
+            .with_threads(4)
+            .find(PathBuf::from("../testdata"))
+            .expect("parallel walk failed");
+        assert_eq!(sequential.tools.len(), parallel.tools.len());
+        for (s, p) in sequential.tools.iter().zip(parallel.tools.iter()) {
+            assert_eq!(s.path, p.path);
+            assert_eq!(s.def.name, p.def.name);
+        }
+    }
+
+    #[test]
+    fn test_multi_match() {
+        let defs = BuildToolDefs::new(vec![
+            BuildToolDef::new(
+                "first".to_string(),
+                vec!["Somefile".to_string()],
+                "http://example.com".to_string(),
+            ),
+            BuildToolDef::new(
+                "other".to_string(),
+                vec!["Otherfile".to_string()],
+                "http://example.com".to_string(),
+            ),
+            BuildToolDef::new(
+                "second".to_string(),
+                vec!["some/*".to_string()],
+                "http://example.com".to_string(),
+            ),
+        ]);
+        let meister = Meister::new(defs, vec![]).unwrap();
+        let source = Source::Directory(Path::new("."));
+        let bt = find_build_tool(&meister, &PathBuf::from("some/Somefile"), &source).unwrap();
+        assert_eq!("first", bt.def.name);
+        assert!(bt.alternatives.is_empty());
+
+        let meister = meister.with_multi_match(true);
+        let bt = find_build_tool(&meister, &PathBuf::from("some/Somefile"), &source).unwrap();
+        assert_eq!("first", bt.def.name);
+        assert_eq!(1, bt.alternatives.len());
+        assert_eq!("second", bt.alternatives[0].name);
+    }
+
+    #[test]
+    fn test_content_matcher() {
+        let defs = BuildToolDefs::new(vec![
+            BuildToolDef::new(
+                "literal".to_string(),
+                vec!["Cargo.toml".to_string()],
+                "http://example.com".to_string(),
+            )
+            .with_contents(vec!["[workspace]".to_string()]),
+            BuildToolDef::new(
+                "regex".to_string(),
+                vec!["Cargo.toml".to_string()],
+                "http://example.com".to_string(),
+            )
+            .with_contents(vec!["re:(?m)^name\\s*=\\s*\"hello\"".to_string()]),
+        ]);
+        let meister = Meister::new(defs, vec![]).unwrap();
+        for project in ["../testdata/hello", "../testdata/hello.tar.gz"] {
+            let r = meister.find(PathBuf::from(project));
+            assert!(r.is_ok());
+            if let Ok(r) = r {
+                assert_eq!(1, r.tools.len());
+                assert_eq!("regex", r.tools[0].def.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_content_precedence() {
+        let dir = std::env::temp_dir().join(format!("btmeister-contents-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for (path, content) in [
+            (
+                "automake/Makefile",
+                "# Makefile.in generated by automake 1.16.5 from Makefile.am.\n",
+            ),
+            ("make/Makefile", "all:\n"),
+            ("poetry/pyproject.toml", "[tool.poetry]\nname = \"hello\"\n"),
+            ("pep621/pyproject.toml", "[project]\nname = \"hello\"\n"),
+            ("groovy/build.gradle", "plugins { id 'java' }\n"),
+            ("kotlin/build.gradle.kts", "plugins { java }\n"),
+        ] {
+            std::fs::create_dir_all(dir.join(path).parent().unwrap()).unwrap();
+            std::fs::write(dir.join(path), content).unwrap();
+        }
+        let names = |meister: &Meister| {
+            let r = meister.find(dir.clone()).unwrap();
+            (0..r.tools.len())
+                .map(|i| (r.path_of(i).unwrap(), r.tools[i].def.name.clone()))
+                .collect::<Vec<(String, String)>>()
+        };
+        let meister = Meister::default();
+        assert_eq!(
+            vec![
+                ("automake/Makefile".to_string(), "automake".to_string()),
+                ("groovy/build.gradle".to_string(), "Gradle".to_string()),
+                ("kotlin/build.gradle.kts".to_string(), "Gradle".to_string()),
+                ("make/Makefile".to_string(), "Make".to_string()),
+                ("poetry/pyproject.toml".to_string(), "Poetry".to_string()),
+            ],
+            names(&meister)
+        );
+
+        let meister = meister.with_multi_match(true);
+        let r = meister.find(dir.join("automake")).unwrap();
+        assert_eq!("automake", r.tools[0].def.name);
+        assert_eq!(
+            vec!["Make"],
+            r.tools[0]
+                .alternatives
+                .iter()
+                .map(|d| d.name.as_str())
+                .collect::<Vec<&str>>()
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_versions_in_archive() {
+        let file = PathBuf::from("../testdata/fibonacci.zip");
+        let r = Meister::default().find(file.clone()).unwrap();
+        assert_eq!(
+            (None, false),
+            (r.tools[0].version.clone(), r.tools[0].wrapper)
+        );
+
+        let r = Meister::default().with_versions(true).find(file).unwrap();
+        assert_eq!(
+            (Some("7.4.2".to_string()), true),
+            (r.tools[0].version.clone(), r.tools[0].wrapper)
+        );
+    }
+
+    #[test]
+    fn test_find_iter() {
+        let meister = Meister::default();
+        let iter = meister.find_iter(PathBuf::from("../testdata/fibonacci"));
+        assert!(iter.is_ok());
+        if let Ok(iter) = iter {
+            assert_eq!(PathBuf::from("../testdata/fibonacci"), iter.base());
+            let tools = iter.collect::<Result<Vec<BuildTool>>>().unwrap();
+            assert_eq!(1, tools.len());
+            assert_eq!("Gradle", tools[0].def.name);
+        }
+        assert!(meister
+            .find_iter(PathBuf::from("../testdata/project_list.txt"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_find_with_stops_early() {
+        let meister = Meister::default();
+        let mut count = 0;
+        let r = meister.find_with(PathBuf::from("../testdata"), |item| {
+            assert!(item.is_ok());
+            count += 1;
+            false
+        });
+        assert!(r.is_ok());
+        assert_eq!(1, count);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_find_async() {
+        let meister = Meister::default();
+        let r = meister
+            .find_async(PathBuf::from("../testdata/fibonacci"))
+            .await;
+        assert!(r.is_ok());
+        if let Ok(r) = r {
+            assert_eq!(1, r.tools.len());
+            assert_eq!("Gradle", r.tools[0].def.name);
+        }
+        let r = meister
+            .find_async(PathBuf::from("../testdata/hello.tar.gz"))
+            .await;
+        assert!(r.is_ok());
+        if let Ok(r) = r {
+            assert_eq!(1, r.tools.len());
+            assert_eq!("Cargo", r.tools[0].def.name);
+        }
+    }
+
+    #[test]
+    fn test_find_tasks() {
+        let meister = Meister::default().with_tasks(true);
+        let source = Source::Directory(Path::new(".."));
+        let bt = find_build_tool(&meister, &PathBuf::from("Justfile"), &source).unwrap();
+        assert_eq!("just", bt.def.name);
+        assert!(bt.tasks.contains(&"build".to_string()));
+
+        let meister = meister.with_tasks(false);
+        let bt = find_build_tool(&meister, &PathBuf::from("Justfile"), &source).unwrap();
+        assert!(bt.tasks.is_empty());
+    }
+
+    #[test]
+    fn test_nested_archive_file() {
+        let base = PathBuf::from("../testdata/nested.tar.gz");
+        let meister = Meister::default();
+        assert!(meister.find(base.clone()).unwrap().tools.is_empty());
+        let r = meister.with_archive_depth(2).find(base).unwrap();
+        let paths = (0..r.tools.len())
+            .map(|i| r.path_of(i).unwrap())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            vec![
+                "nested/lib/app.jar!/pom.xml",
+                "nested/lib/bundle.zip!/inner/lib.jar!/build.gradle"
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn test_open_archives() {
+        let base = PathBuf::from("../testdata");
+        let meister = Meister::default()
+            .with_open_archives(true)
+            .with_archive_depth(1);
+        let r = meister.find(base.clone()).unwrap();
+        let paths = (0..r.tools.len())
+            .map(|i| r.path_of(i).unwrap())
+            .collect::<Vec<String>>();
+        assert!(paths.contains(&"hello.tar.gz!/hello/Cargo.toml".to_string()));
+        assert!(paths.contains(&"nested.tar.gz!/nested/lib/app.jar!/pom.xml".to_string()));
+        assert!(paths.contains(&"fibonacci/build.gradle".to_string()));
+
+        let parallel = meister.with_threads(2).find(base).unwrap();
+        assert_eq!(r.tools.len(), parallel.tools.len());
+    }
+
+    #[test]
+    fn test_ignores_in_archive() {
+        let file =
+            std::env::temp_dir().join(format!("btmeister-ignores-{}.tar", std::process::id()));
+        let mut builder = ::tar::Builder::new(std::fs::File::create(&file).unwrap());
+        for (name, content) in [
+            ("p/.gitignore", "vendor/\n"),
+            ("p/Makefile", "all:\n"),
+            ("p/vendor/pom.xml", "<project/>\n"),
+            ("p/.hidden/build.gradle", "\n"),
+        ] {
+            let mut header = ::tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, content.as_bytes())
+                .unwrap();
+        }
+        builder.finish().unwrap();
+        drop(builder);
+
+        let names = |meister: Meister| {
+            let r = meister.find(file.clone()).unwrap();
+            (0..r.tools.len())
+                .map(|i| r.path_of(i).unwrap())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            vec!["p/Makefile", "p/.hidden/build.gradle"],
+            names(Meister::default())
+        );
+        let hidden = Meister::new(BuildToolDefs::default(), vec![IgnoreType::Hidden]).unwrap();
+        assert_eq!(vec!["p/Makefile", "p/vendor/pom.xml"], names(hidden));
+        let _ = std::fs::remove_file(file);
+    }
+
     #[test]
     fn test_archive_file() {
         let meister = Meister::default();
@@ -578,27 +1638,152 @@ mod tests {
     }
 
     #[test]
-    fn test_partial_matcher() {
-        let matcher = PartialMatcher {
-            pattern: "testdata".to_string(),
-        };
+    fn test_exclude_matcher() {
+        let matcher = ExcludeMatcher::new(vec!["testdata".to_string()]).unwrap();
         assert!(matcher.matches(&PathBuf::from("testdata/hello.txt")));
         assert!(matcher.matches(&PathBuf::from("target/testdata/hello.txt")));
         assert!(matcher.matches(&PathBuf::from("./testdata")));
         assert!(matcher.matches(&PathBuf::from("testdata")));
+        assert!(!matcher.matches(&PathBuf::from("latest/Makefile")));
+        assert!(!matcher.matches(&PathBuf::from("mytestdata/Makefile")));
     }
 
     #[test]
-    fn test_multiple_partial_matcher() {
-        let matcher = MultipleMatcher {
-            matchers: vec![Box::new(PartialMatcher {
-                pattern: "testdata".to_string(),
-            })],
-        };
-        assert!(matcher.matches(&PathBuf::from("testdata/hello.txt")));
-        assert!(matcher.matches(&PathBuf::from("target/testdata/hello.txt")));
-        assert!(matcher.matches(&PathBuf::from("./testdata")));
-        assert!(matcher.matches(&PathBuf::from("testdata")));
+    fn test_exclude_matcher_anchor_and_negation() {
+        let matcher = ExcludeMatcher::new(vec![
+            "/test".to_string(),
+            "*.md".to_string(),
+            "!README.md".to_string(),
+            "re:^docs/.*\\.ya?ml$".to_string(),
+        ])
+        .unwrap();
+        assert!(matcher.matches(&PathBuf::from("test/Makefile")));
+        assert!(!matcher.matches(&PathBuf::from("sub/test/Makefile")));
+        assert!(!matcher.matches(&PathBuf::from("contest/Makefile")));
+        assert!(matcher.matches(&PathBuf::from("sub/CHANGES.md")));
+        assert!(!matcher.matches(&PathBuf::from("sub/README.md")));
+        assert!(matcher.matches(&PathBuf::from("docs/site/config.yaml")));
+        assert!(!matcher.matches(&PathBuf::from("site/docs/config.yaml")));
+    }
+
+    #[test]
+    fn test_invalid_exclude_pattern() {
+        assert!(ExcludeMatcher::new(vec!["re:(".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_excludes_in_directory_and_archive() {
+        let meister = Meister::new_with_excludes(
+            BuildToolDefs::default(),
+            vec![],
+            vec!["/fibonacci".to_string(), "hello/".to_string()],
+        )
+        .unwrap();
+        let r = meister.find(PathBuf::from("../testdata")).unwrap();
+        assert!(r.tools.is_empty());
+        let r = meister
+            .find(PathBuf::from("../testdata/hello.tar"))
+            .unwrap();
+        assert!(r.tools.is_empty());
+        let r = meister
+            .find(PathBuf::from("../testdata/fibonacci.zip"))
+            .unwrap();
+        assert!(r.tools.is_empty());
+    }
+
+    #[test]
+    fn test_languages() {
+        let meister = Meister::default().with_languages(LanguageDefs::default());
+        let r = meister
+            .find(PathBuf::from("../testdata/fibonacci"))
+            .unwrap();
+        assert!(r.languages.iter().any(|l| l.name == "Java" && l.files > 0));
+
+        let r = meister
+            .find(PathBuf::from("../testdata/hello.tar"))
+            .unwrap();
+        assert!(!r.languages.is_empty());
+
+        // the files in the archives are counted in the same way by the parallel traversal.
+        let meister = meister.with_open_archives(true).with_archive_depth(1);
+        let r = meister.find(PathBuf::from("../testdata")).unwrap();
+        let parallel = meister
+            .with_threads(4)
+            .find(PathBuf::from("../testdata"))
+            .unwrap();
+        assert_eq!(r.languages, parallel.languages);
+
+        let r = Meister::default()
+            .find(PathBuf::from("../testdata/fibonacci"))
+            .unwrap();
+        assert!(r.languages.is_empty());
+    }
+
+    #[test]
+    fn test_git_revision() {
+        let repo = git::tests::fixture("meister");
+        let meister = Meister::default();
+        let r = meister
+            .find(PathBuf::from(format!("{}@v1", repo.display())))
+            .unwrap();
+        assert_eq!(
+            vec!["Make"],
+            r.tools
+                .iter()
+                .map(|bt| bt.def.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        let r = meister
+            .find(PathBuf::from(format!("{}@v2", repo.display())))
+            .unwrap();
+        assert_eq!(
+            vec!["Make", "Gradle"],
+            r.tools
+                .iter()
+                .map(|bt| bt.def.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(meister
+            .find(PathBuf::from(format!("{}@v9", repo.display())))
+            .is_err());
+        let _ = std::fs::remove_dir_all(repo.parent().unwrap());
+    }
+
+    #[test]
+    fn test_tool_filter() {
+        let filter = ToolFilter::new(vec!["gradle".to_string()], vec![]);
+        let meister = Meister::default().with_tool_filter(filter).unwrap();
+        let r = meister
+            .find(PathBuf::from("../testdata/fibonacci"))
+            .unwrap();
+        assert!(!r.tools.is_empty());
+        assert!(r.tools.iter().all(|bt| bt.def.name == "Gradle"));
+
+        let filter = ToolFilter::new(vec![], vec!["Gradle".to_string()]);
+        let meister = Meister::default().with_tool_filter(filter).unwrap();
+        let r = meister
+            .find(PathBuf::from("../testdata/fibonacci"))
+            .unwrap();
+        assert!(r.tools.iter().all(|bt| bt.def.name != "Gradle"));
+    }
+
+    #[test]
+    fn test_unknown_tool() {
+        let filter = ToolFilter::new(vec!["Gradel".to_string()], vec!["xyzzy".to_string()]);
+        match filter.validate(&BuildToolDefs::default()) {
+            Err(MeisterError::Array(errs)) => {
+                assert_eq!(2, errs.len());
+                match &errs[0] {
+                    MeisterError::UnknownTool(name, Some(s)) => {
+                        assert_eq!("Gradel", name);
+                        assert_eq!("Gradle", s);
+                    }
+                    e => panic!("unexpected error: {:?}", e),
+                }
+                assert!(matches!(&errs[1], MeisterError::UnknownTool(_, None)));
+            }
+            _ => panic!("unknown tool names should be an error"),
+        }
     }
 
     #[test]
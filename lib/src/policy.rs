@@ -0,0 +1,338 @@
+/*!
+ * This module checks the detected build tools against the policy of the organization.
+ * The policy is given as a JSON file ([JSON schema](https://github.com/tamada/btmeister/blob/main/assets/policy.json.schema)),
+ * and every rule in the file is optional.
+ *
+ * ```json
+ * {
+ *   "allow": ["Gradle", "Make", "GitHub Actions", "GitLab CI/CD"],
+ *   "deny": ["Travis"],
+ *   "require-one-of": [["GitHub Actions", "GitLab CI/CD"]],
+ *   "max-build-tools": 1,
+ *   "max-depth": 3
+ * }
+ * ```
+ *
+ * - `allow`: the build tools which may be used. The build tools not listed are violations.
+ * - `deny`: the build tools which must not be used.
+ * - `require-one-of`: the groups of the build tools, and at least one build tool of each group must be used.
+ * - `max-build-tools`: the maximum number of the primary build tools, that is, the build tools whose main category is `build`
+ *   (the build tools without categories are also counted, see [BuildToolDef::main_category](crate::defs::BuildToolDef::main_category)).
+ * - `max-depth`: the maximum depth of the build files from the project base (`Makefile` is 0, `sub/Makefile` is 1).
+ *
+ * The names of the build tools are compared ignoring the case, and [Policy::validate] reports the names not in the definitions.
+ */
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::fs::OpenOptions;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::defs::{BuildToolDefs, Category};
+use crate::{validate_names, BuildTools, MeisterError, Result};
+
+/// Policy represents the rules for the build tools of the projects.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Policy {
+    /// the names of the build tools which may be used. `None` allows all of the build tools.
+    pub allow: Option<Vec<String>>,
+    /// the names of the build tools which must not be used.
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// the groups of the build tools, at least one build tool of each group must be used.
+    #[serde(default)]
+    pub require_one_of: Vec<Vec<String>>,
+    /// the maximum number of the primary build tools, whose main category is [Category::Build].
+    pub max_build_tools: Option<usize>,
+    /// the maximum depth of the build files from the project base.
+    pub max_depth: Option<usize>,
+}
+
+/// Rule represents the kind of the rules in [Policy].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+    Allow,
+    Deny,
+    RequireOneOf,
+    MaxBuildTools,
+    MaxDepth,
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Rule::Allow => "allow",
+            Rule::Deny => "deny",
+            Rule::RequireOneOf => "require-one-of",
+            Rule::MaxBuildTools => "max-build-tools",
+            Rule::MaxDepth => "max-depth",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Violation represents a failure of a rule in a project.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// the failed rule.
+    pub rule: Rule,
+    /// the relative path of the build file violating the rule, if the rule is about a file.
+    pub path: Option<String>,
+    /// the description of the violation.
+    pub message: String,
+}
+
+/// Report represents the result of the policy check of a project.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    /// the base of the checked project.
+    pub base: PathBuf,
+    /// the violations in the order of the rules.
+    pub violations: Vec<Violation>,
+}
+
+impl Report {
+    /// is_ok returns true if the project satisfies all of the rules.
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl Policy {
+    /// parse parses the policy from the given JSON file.
+    pub fn parse(path: PathBuf) -> Result<Policy> {
+        match OpenOptions::new().read(true).open(path) {
+            Ok(file) => match serde_json::from_reader(BufReader::new(file)) {
+                Ok(policy) => Ok(policy),
+                Err(e) => Err(MeisterError::Json(e)),
+            },
+            Err(e) => Err(MeisterError::IO(e)),
+        }
+    }
+
+    /// validate checks that all of the names in the rules are defined in the given definitions.
+    /// Like [ToolFilter::validate](crate::ToolFilter::validate), the names should be validated
+    /// against the whole definitions before filtering them by the categories.
+    pub fn validate(&self, defs: &BuildToolDefs) -> Result<()> {
+        let names = self
+            .allow
+            .iter()
+            .flatten()
+            .chain(self.deny.iter())
+            .chain(self.require_one_of.iter().flatten());
+        validate_names(names, defs)
+    }
+
+    /// check evaluates the rules of this policy against the given result.
+    pub fn check(&self, tools: &BuildTools) -> Report {
+        let mut violations = vec![];
+        let paths = (0..tools.tools.len())
+            .map(|index| tools.path_of(index).unwrap_or_default())
+            .collect::<Vec<String>>();
+        for (bt, path) in tools.tools.iter().zip(paths.iter()) {
+            if let Some(allow) = self.allow.as_ref().filter(|a| !contains(a, &bt.def.name)) {
+                violations.push(Violation {
+                    rule: Rule::Allow,
+                    path: Some(path.clone()),
+                    message: format!("{} is not allowed (only {})", bt.def.name, allow.join(", ")),
+                });
+            }
+        }
+        for (bt, path) in tools.tools.iter().zip(paths.iter()) {
+            if contains(&self.deny, &bt.def.name) {
+                violations.push(Violation {
+                    rule: Rule::Deny,
+                    path: Some(path.clone()),
+                    message: format!("{} is denied", bt.def.name),
+                });
+            }
+        }
+        for group in &self.require_one_of {
+            if !tools.tools.iter().any(|bt| contains(group, &bt.def.name)) {
+                violations.push(Violation {
+                    rule: Rule::RequireOneOf,
+                    path: None,
+                    message: format!("none of {} is used", group.join(", ")),
+                });
+            }
+        }
+        if let Some(max) = self.max_build_tools {
+            let names = tools
+                .tools
+                .iter()
+                .filter(|bt| bt.def.main_category() == Category::Build)
+                .map(|bt| bt.def.name.clone())
+                .collect::<BTreeSet<String>>();
+            if names.len() > max {
+                violations.push(Violation {
+                    rule: Rule::MaxBuildTools,
+                    path: None,
+                    message: format!(
+                        "{} primary build tools are used (at most {}): {}",
+                        names.len(),
+                        max,
+                        names.into_iter().collect::<Vec<String>>().join(", ")
+                    ),
+                });
+            }
+        }
+        if let Some(max) = self.max_depth {
+            for path in paths.iter() {
+                let depth = depth(path);
+                if depth > max {
+                    violations.push(Violation {
+                        rule: Rule::MaxDepth,
+                        path: Some(path.clone()),
+                        message: format!("the depth {} exceeds {}", depth, max),
+                    });
+                }
+            }
+        }
+        Report {
+            base: tools.base.clone(),
+            violations,
+        }
+    }
+}
+
+fn contains(names: &[String], name: &str) -> bool {
+    names.iter().any(|n| n.eq_ignore_ascii_case(name))
+}
+
+fn depth(path: &str) -> usize {
+    Path::new(path).components().count().saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defs::BuildToolDef;
+    use crate::BuildTool;
+
+    fn tool(path: &str, name: &str, category: Category) -> BuildTool {
+        BuildTool::new(
+            PathBuf::from("base").join(path),
+            BuildToolDef::new(name.to_string(), vec![], "".to_string())
+                .with_categories(vec![category]),
+        )
+    }
+
+    #[test]
+    fn test_check() {
+        let tools = BuildTools::new(
+            PathBuf::from("base"),
+            vec![
+                tool(".travis.yml", "Travis", Category::Ci),
+                tool("build.gradle", "Gradle", Category::Build),
+                tool("sub/pom.xml", "Apache Maven", Category::Build),
+                tool("a/b/c/Makefile", "Make", Category::TaskRunner),
+            ],
+        );
+        let policy: Policy = serde_json::from_str(
+            r#"{"deny":["travis"],"require-one-of":[["GitHub Actions","GitLab CI/CD"],["Gradle"]],"max-build-tools":1,"max-depth":2}"#,
+        )
+        .unwrap();
+        let report = policy.check(&tools);
+        assert!(!report.is_ok());
+        assert_eq!(
+            vec![
+                (Rule::Deny, Some(".travis.yml")),
+                (Rule::RequireOneOf, None),
+                (Rule::MaxBuildTools, None),
+                (Rule::MaxDepth, Some("a/b/c/Makefile")),
+            ],
+            report
+                .violations
+                .iter()
+                .map(|v| (v.rule, v.path.as_deref()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "2 primary build tools are used (at most 1): Apache Maven, Gradle",
+            report.violations[2].message
+        );
+
+        assert!(Policy::default().check(&tools).is_ok());
+        assert!(serde_json::from_str::<Policy>(r#"{"allow":["travis"]}"#).is_ok());
+        assert!(serde_json::from_str::<Policy>(r#"{"denied":["Travis"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_allow() {
+        let tools = BuildTools::new(
+            PathBuf::from("base"),
+            vec![
+                tool(".travis.yml", "Travis", Category::Ci),
+                tool("build.gradle", "Gradle", Category::Build),
+                tool("Makefile", "Make", Category::TaskRunner),
+                tool("Dockerfile", "Docker", Category::Container),
+            ],
+        );
+        let policy: Policy =
+            serde_json::from_str(r#"{"allow":["gradle","Make"],"max-build-tools":1}"#).unwrap();
+        let report = policy.check(&tools);
+        assert_eq!(
+            vec![
+                (Rule::Allow, Some(".travis.yml")),
+                (Rule::Allow, Some("Dockerfile")),
+            ],
+            report
+                .violations
+                .iter()
+                .map(|v| (v.rule, v.path.as_deref()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "Travis is not allowed (only gradle, Make)",
+            report.violations[0].message
+        );
+        let policy: Policy = serde_json::from_str(r#"{"allow":[]}"#).unwrap();
+        assert_eq!(4, policy.check(&tools).violations.len());
+    }
+
+    #[test]
+    fn test_uncategorized_build_tools() {
+        let tools = BuildTools::new(
+            PathBuf::from("base"),
+            vec![
+                tool("build.gradle", "Gradle", Category::Build),
+                BuildTool::new(
+                    PathBuf::from("base/build.xml"),
+                    BuildToolDef::new("Ant".to_string(), vec![], "".to_string()),
+                ),
+            ],
+        );
+        let policy: Policy = serde_json::from_str(r#"{"max-build-tools":1}"#).unwrap();
+        let report = policy.check(&tools);
+        assert_eq!(
+            "2 primary build tools are used (at most 1): Ant, Gradle",
+            report.violations[0].message
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let defs = BuildToolDefs::default();
+        let policy: Policy = serde_json::from_str(
+            r#"{"allow":["gradle","Make"],"deny":["Travis"],"require-one-of":[["GitHub Actions"]]}"#,
+        )
+        .unwrap();
+        assert!(policy.validate(&defs).is_ok());
+
+        let policy: Policy =
+            serde_json::from_str(r#"{"deny":["xyzzy"],"require-one-of":[["Gradel"]]}"#).unwrap();
+        match policy.validate(&defs) {
+            Err(MeisterError::Array(errs)) => {
+                assert_eq!(2, errs.len());
+                assert!(matches!(&errs[0], MeisterError::UnknownTool(n, None) if n == "xyzzy"));
+                assert!(
+                    matches!(&errs[1], MeisterError::UnknownTool(n, Some(c)) if n == "Gradel" && c == "Gradle")
+                );
+            }
+            r => panic!("unexpected: {:?}", r),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+/*!
+ * This module ranks the detected build tools of a project, and estimates the primary build tool.
+ * The score of each build tool is calculated from the following factors.
+ *
+ * - the weight of the main category of the build tool (the build tools are weighted more than CI services or containers),
+ * - the depth of the shallowest build file from the project base, and
+ * - the number of the build files.
+ *
+ * The confidence of a build tool is its score divided by the sum of the scores of all build tools in the project.
+ */
+use std::collections::HashMap;
+
+use crate::defs::{BuildToolDef, Category};
+use crate::BuildTools;
+
+/// rank calculates the confidence of each build tool in the given result.
+pub(crate) fn rank(tools: &mut BuildTools) {
+    let mut stats = HashMap::<String, (f64, usize, usize)>::new();
+    for (index, bt) in tools.tools.iter().enumerate() {
+        let depth = depth_of(tools, index);
+        let entry = stats
+            .entry(bt.def.name.clone())
+            .or_insert((weight(&bt.def), usize::MAX, 0));
+        entry.1 = entry.1.min(depth);
+        entry.2 += 1;
+    }
+    let scores = stats
+        .iter()
+        .map(|(name, (weight, depth, count))| (name.clone(), score(*weight, *depth, *count)))
+        .collect::<HashMap<String, f64>>();
+    let total = scores.values().sum::<f64>();
+    for bt in tools.tools.iter_mut() {
+        bt.confidence = match scores.get(&bt.def.name) {
+            Some(score) if total > 0.0 => score / total,
+            _ => 0.0,
+        };
+    }
+}
+
+fn depth_of(tools: &BuildTools, index: usize) -> usize {
+    match tools.path_of(index) {
+        Ok(p) => std::path::Path::new(&p)
+            .components()
+            .count()
+            .saturating_sub(1),
+        Err(_) => 0,
+    }
+}
+
+fn score(weight: f64, depth: usize, count: usize) -> f64 {
+    weight / (1.0 + depth as f64) * (1.0 + (count as f64).ln())
+}
+
+/// weight returns the weight of the main category of the given build tool ([BuildToolDef::main_category]).
+fn weight(def: &BuildToolDef) -> f64 {
+    match def.main_category() {
+        Category::Build => 1.0,
+        Category::PackageManager => 0.8,
+        Category::TaskRunner => 0.6,
+        Category::Docs => 0.5,
+        Category::Container | Category::Iac => 0.3,
+        Category::Ci => 0.2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildTool;
+    use std::path::PathBuf;
+
+    fn tool(path: &str, name: &str, category: Category) -> BuildTool {
+        BuildTool::new(
+            PathBuf::from(path),
+            BuildToolDef::new(name.to_string(), vec![], "".to_string())
+                .with_categories(vec![category]),
+        )
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut tools = BuildTools::new(
+            PathBuf::from("base"),
+            vec![
+                tool("base/Dockerfile", "Docker", Category::Container),
+                tool(
+                    "base/.github/workflows/build.yml",
+                    "GitHub Actions",
+                    Category::Ci,
+                ),
+                tool(
+                    "base/.github/workflows/publish.yml",
+                    "GitHub Actions",
+                    Category::Ci,
+                ),
+                tool("base/Makefile", "Make", Category::TaskRunner),
+                tool("base/build.gradle", "Gradle", Category::Build),
+                tool("base/sub/build.gradle", "Gradle", Category::Build),
+            ],
+        );
+        rank(&mut tools);
+        let primary = tools.primary();
+        assert!(primary.is_some_and(|d| d.name == "Gradle"));
+        let total = tools
+            .tools
+            .iter()
+            .filter(|bt| bt.path.ends_with("Dockerfile") || bt.path.ends_with("Makefile"))
+            .map(|bt| bt.confidence)
+            .sum::<f64>();
+        assert!(total < tools.tools[4].confidence);
+        assert_eq!(tools.tools[4].confidence, tools.tools[5].confidence);
+    }
+
+    #[test]
+    fn test_rank_empty() {
+        let mut tools = BuildTools::new(PathBuf::from("base"), vec![]);
+        rank(&mut tools);
+        assert!(tools.primary().is_none());
+    }
+}
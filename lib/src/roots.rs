@@ -0,0 +1,329 @@
+/*!
+ * This module detects the project roots in the archive files.
+ *
+ * The archive files often wrap the project in a top-level directory (e.g., `fibonacci/build.gradle` in `fibonacci.zip`),
+ * or hold several projects side by side (e.g., `app/pom.xml` and `web/package.json` in `dist.tar.gz`).
+ * The project roots are the shallowest directories containing the detected build files,
+ * and the build files in their subdirectories belong to them.
+ * The build files matched by the path patterns (e.g., `.circleci/config.yml`) belong to the directory
+ * containing the pattern, and the patterns are matched again relative to each project root.
+ * Each project root is reported as a [BuildTools] whose base is the archive file and the root joined with `!/`,
+ * e.g., `fibonacci.zip!/fibonacci`.
+ */
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::defs::BuildToolDef;
+use crate::extractors::SEPARATOR;
+use crate::{
+    build_result, find_build_tool, find_build_tool_as, is_supported_archive_format, BuildTool,
+    BuildTools, Matcher, Meister, PathGlobMatcher, Result, Source,
+};
+
+impl Meister {
+    /// find_projects detects the build tools in the specified directory or archive file, like [Meister::find].
+    /// For the archive files, this function detects the project roots in the archive file,
+    /// and returns a [BuildTools] for each root with the paths relative to the root.
+    /// If the build files are found at the top of the archive file (or no build files are found),
+    /// the result is the same as [Meister::find].
+    pub fn find_projects(&self, base: PathBuf) -> Result<Vec<BuildTools>> {
+        if !base.is_file() || !is_supported_archive_format(&base) {
+            return Ok(vec![self.find(base)?]);
+        }
+        // the archive file is listed only once, and the entries not ignored nor excluded are shared
+        // by the detection in the whole archive file and the detection relative to each project root.
+        let listing = self.list_archive(&base)?;
+        let entries = listing
+            .entries
+            .iter()
+            .filter(|e| !self.excludes.matches(Path::new(e.as_str())))
+            .collect::<Vec<&String>>();
+        let source = Source::Archive(&listing.contents);
+        let whole = entries
+            .iter()
+            .filter_map(|e| find_build_tool(self, Path::new(e.as_str()), &source))
+            .collect::<Vec<BuildTool>>();
+        let languages = |root: &Path| match &self.census {
+            Some(census) => {
+                let mut counts = census.counter();
+                for entry in entries.iter().filter(|e| !e.ends_with('/')) {
+                    if let Ok(rel) = Path::new(entry.as_str()).strip_prefix(root) {
+                        census.count(rel, &mut counts);
+                    }
+                }
+                census.result(&counts)
+            }
+            None => vec![],
+        };
+        let roots = project_roots(&whole);
+        if roots.iter().all(|root| root.as_os_str().is_empty()) {
+            let languages = languages(Path::new(""));
+            return Ok(vec![build_result(base, whole, vec![], languages)?]);
+        }
+        let detected = whole
+            .iter()
+            .map(|bt| bt.path.as_path())
+            .collect::<HashSet<&Path>>();
+        let mut result = vec![];
+        for root in roots {
+            let root_base =
+                PathBuf::from(format!("{}{}{}", base.display(), SEPARATOR, root.display()));
+            let mut tools = whole
+                .iter()
+                .filter_map(|bt| {
+                    let rel = bt.path.strip_prefix(&root).ok()?;
+                    let mut bt = bt.clone();
+                    bt.path = root_base.join(rel);
+                    Some(bt)
+                })
+                .collect::<Vec<BuildTool>>();
+            // the path patterns of the definitions (e.g., `.github/workflows/*.yml`) are anchored to the project root,
+            // therefore, the entries not detected yet are matched again with the paths relative to the root.
+            for entry in entries.iter().filter(|e| !e.ends_with('/')) {
+                let path = Path::new(entry.as_str());
+                let rel = match path.strip_prefix(&root) {
+                    Ok(rel) if !detected.contains(path) => rel,
+                    _ => continue,
+                };
+                if let Some(mut bt) = find_build_tool_as(self, path, rel, &source) {
+                    bt.path = root_base.join(rel);
+                    tools.push(bt);
+                }
+            }
+            result.push(build_result(root_base, tools, vec![], languages(&root))?);
+        }
+        Ok(result)
+    }
+}
+
+/// project_roots returns the shallowest project directories of the given build files in the archive file.
+fn project_roots(tools: &[BuildTool]) -> Vec<PathBuf> {
+    let dirs = tools.iter().map(project_dir).collect::<BTreeSet<PathBuf>>();
+    dirs.iter()
+        .filter(|dir| {
+            !dirs
+                .iter()
+                .any(|other| other != *dir && dir.starts_with(other))
+        })
+        .cloned()
+        .collect()
+}
+
+/// project_dir returns the project directory of the given build file.
+/// The directories in the matched pattern of the definition are removed from the path,
+/// e.g., `repo/.github/workflows/ci.yml` belongs to `repo`, not `repo/.github/workflows`.
+/// The build files in the nested archives belong to the directory of the nested archive.
+fn project_dir(bt: &BuildTool) -> PathBuf {
+    let path = bt.path.to_string_lossy();
+    let (outer, depth) = match path.split_once(SEPARATOR) {
+        Some((outer, _)) => (Path::new(outer), 0),
+        None => (bt.path.as_path(), pattern_depth(&bt.def, &bt.path)),
+    };
+    outer
+        .ancestors()
+        .nth(depth + 1)
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+/// pattern_depth returns the number of the directories in the build file pattern of the definition
+/// which matches the tail of the given path. The result is 0 for the patterns of the file names.
+fn pattern_depth(def: &BuildToolDef, path: &Path) -> usize {
+    let components = path.components().collect::<Vec<_>>();
+    def.build_files
+        .iter()
+        .filter(|pattern| pattern.contains('/'))
+        .find_map(|pattern| {
+            let depth = Path::new(pattern).components().count().saturating_sub(1);
+            let tail = components
+                .get(components.len().checked_sub(depth + 1)?..)?
+                .iter()
+                .collect::<PathBuf>();
+            let matcher = PathGlobMatcher::new(pattern).ok()?;
+            matcher.matches(&tail).then_some(depth)
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defs::BuildToolDef;
+
+    fn tool(path: &str) -> BuildTool {
+        BuildTool::new(
+            PathBuf::from(path),
+            BuildToolDef::new("Make".to_string(), vec![], "".to_string()),
+        )
+    }
+
+    fn workflow(path: &str) -> BuildTool {
+        BuildTool::new(
+            PathBuf::from(path),
+            BuildToolDef::new(
+                "GitHub Actions".to_string(),
+                vec![".github/workflows/*.yml".to_string()],
+                "".to_string(),
+            ),
+        )
+    }
+
+    fn create_tarball(name: &str, files: &[&str]) -> PathBuf {
+        let files = files.iter().map(|f| (*f, "\n")).collect::<Vec<_>>();
+        create_tarball_with(name, &files)
+    }
+
+    fn create_tarball_with(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let file = std::env::temp_dir().join(format!(
+            "btmeister-roots-{}-{}.tar",
+            name,
+            std::process::id()
+        ));
+        let mut builder = ::tar::Builder::new(std::fs::File::create(&file).unwrap());
+        for (name, content) in files {
+            let mut header = ::tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, content.as_bytes())
+                .unwrap();
+        }
+        builder.finish().unwrap();
+        file
+    }
+
+    fn paths(r: &BuildTools) -> Vec<String> {
+        let mut paths = (0..r.tools.len())
+            .map(|i| r.path_of(i).unwrap())
+            .collect::<Vec<String>>();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_project_roots() {
+        let roots = project_roots(&[
+            tool("app/pom.xml"),
+            tool("app/sub/Makefile"),
+            tool("web/client/package.json"),
+            tool("web/client/lib/app.jar!/pom.xml"),
+        ]);
+        assert_eq!(
+            vec![PathBuf::from("app"), PathBuf::from("web/client")],
+            roots
+        );
+        assert_eq!(
+            vec![PathBuf::from("")],
+            project_roots(&[tool("Makefile"), tool("app/pom.xml")])
+        );
+        assert!(project_roots(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_project_roots_of_path_patterns() {
+        assert_eq!(
+            vec![PathBuf::from("")],
+            project_roots(&[workflow(".github/workflows/ci.yml"), tool("app/pom.xml")])
+        );
+        assert_eq!(
+            vec![PathBuf::from("repo"), PathBuf::from("web")],
+            project_roots(&[
+                workflow("repo/.github/workflows/ci.yml"),
+                tool("repo/pom.xml"),
+                tool("web/package.json"),
+            ])
+        );
+        assert_eq!(
+            vec![PathBuf::from("lib")],
+            project_roots(&[workflow("lib/app.jar!/.github/workflows/ci.yml")])
+        );
+    }
+
+    #[test]
+    fn test_find_projects() {
+        let meister = Meister::default();
+        let r = meister
+            .find_projects(PathBuf::from("../testdata/fibonacci.zip"))
+            .unwrap();
+        assert_eq!(1, r.len());
+        assert_eq!(
+            PathBuf::from("../testdata/fibonacci.zip!/fibonacci"),
+            r[0].base
+        );
+        assert_eq!("build.gradle", r[0].path_of(0).unwrap());
+
+        let r = meister
+            .find_projects(PathBuf::from("../testdata/fibonacci"))
+            .unwrap();
+        assert_eq!(1, r.len());
+        assert_eq!(PathBuf::from("../testdata/fibonacci"), r[0].base);
+    }
+
+    #[test]
+    fn test_find_projects_with_path_patterns() {
+        let meister = Meister::default();
+        let file = create_tarball("top", &[".github/workflows/ci.yml", "app/pom.xml"]);
+        let r = meister.find_projects(file.clone()).unwrap();
+        assert_eq!(1, r.len());
+        assert_eq!(file, r[0].base);
+        assert_eq!(
+            vec![".github/workflows/ci.yml", "app/pom.xml"],
+            paths(&r[0])
+        );
+        let _ = std::fs::remove_file(file);
+
+        let file = create_tarball(
+            "wrapped",
+            &[
+                "repo-1.0/.github/workflows/ci.yml",
+                "repo-1.0/.circleci/config.yml",
+                "repo-1.0/pom.xml",
+            ],
+        );
+        let r = meister.find_projects(file.clone()).unwrap();
+        assert_eq!(1, r.len());
+        assert_eq!(
+            PathBuf::from(format!("{}!/repo-1.0", file.display())),
+            r[0].base
+        );
+        assert_eq!(
+            vec![
+                ".circleci/config.yml",
+                ".github/workflows/ci.yml",
+                "pom.xml"
+            ],
+            paths(&r[0])
+        );
+        let names = r[0]
+            .tools
+            .iter()
+            .map(|bt| bt.def.name.as_str())
+            .collect::<BTreeSet<&str>>();
+        assert!(names.contains("GitHub Actions"));
+        let _ = std::fs::remove_file(file);
+    }
+
+    #[test]
+    fn test_find_projects_with_ignores() {
+        let file = create_tarball_with(
+            "ignores",
+            &[
+                ("repo-1.0/.gitignore", ".circleci/\n"),
+                ("repo-1.0/.circleci/config.yml", "\n"),
+                ("repo-1.0/.github/workflows/ci.yml", "\n"),
+                ("repo-1.0/docs/Makefile", "\n"),
+                ("repo-1.0/pom.xml", "\n"),
+            ],
+        );
+        let meister = Meister::new_with_excludes(
+            crate::defs::BuildToolDefs::default(),
+            vec![crate::IgnoreType::GitIgnore],
+            vec!["docs/".to_string()],
+        )
+        .unwrap();
+        let r = meister.find_projects(file.clone()).unwrap();
+        assert_eq!(1, r.len());
+        assert_eq!(vec![".github/workflows/ci.yml", "pom.xml"], paths(&r[0]));
+        let _ = std::fs::remove_file(file);
+    }
+}
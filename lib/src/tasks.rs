@@ -0,0 +1,175 @@
+/*!
+ * This module enumerates the tasks (entry points) defined in the build files of the task runners.
+ * The supported build files are as follows.
+ *
+ * - `Makefile`, `makefile`, and `GNUmakefile`: the targets.
+ * - `Justfile` and `justfile`: the recipes.
+ * - `Taskfile.yml` and its variants: the keys of `tasks`.
+ * - `package.json`: the keys of `scripts` in the order of the declarations.
+ */
+use std::path::Path;
+
+use regex::Regex;
+
+/// list returns the task names defined in the given content of the build file.
+/// If the build file is not supported, this function returns the empty vector.
+pub(crate) fn list(path: &Path, content: &str) -> Vec<String> {
+    let name = match path.file_name() {
+        Some(name) => name.to_string_lossy().to_lowercase(),
+        None => return vec![],
+    };
+    let tasks = match name.as_str() {
+        "makefile" | "gnumakefile" => make_targets(content),
+        "justfile" | ".justfile" => just_recipes(content),
+        "package.json" => npm_scripts(content),
+        n if n.starts_with("taskfile") && (n.ends_with(".yml") || n.ends_with(".yaml")) => {
+            taskfile_tasks(content)
+        }
+        _ => vec![],
+    };
+    dedup(tasks)
+}
+
+fn make_targets(content: &str) -> Vec<String> {
+    let re = Regex::new(r"^([^\s:=#][^:=]*?)\s*:(?:[^=:]|$)").unwrap();
+    content
+        .lines()
+        .filter_map(|line| re.captures(line))
+        .flat_map(|c| {
+            c[1].split_whitespace()
+                .map(|s| s.to_string())
+                .collect::<Vec<String>>()
+        })
+        .filter(|t| !t.starts_with('.') && !t.contains('%') && !t.contains('$'))
+        .collect()
+}
+
+fn just_recipes(content: &str) -> Vec<String> {
+    let re = Regex::new(r"^@?([A-Za-z_][A-Za-z0-9_-]*)\b[^:]*?:(?:[^=]|$)").unwrap();
+    content
+        .lines()
+        .filter_map(|line| re.captures(line))
+        .map(|c| c[1].to_string())
+        .filter(|name| !matches!(name.as_str(), "alias" | "export" | "import" | "mod" | "set"))
+        .collect()
+}
+
+fn taskfile_tasks(content: &str) -> Vec<String> {
+    let key = Regex::new(r#"^(\s+)("[^"]*"|'[^']*'|[^\s#'"-][^#]*?):(?:\s|$)"#).unwrap();
+    let mut result = vec![];
+    let mut in_tasks = false;
+    let mut indent: Option<usize> = None;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            in_tasks = trimmed.starts_with("tasks:");
+            indent = None;
+            continue;
+        }
+        if !in_tasks {
+            continue;
+        }
+        if let Some(c) = key.captures(line) {
+            let width = c[1].len();
+            if indent.is_none() {
+                indent = Some(width);
+            }
+            if indent == Some(width) {
+                result.push(c[2].trim_matches(|c| c == '"' || c == '\'').to_string());
+            }
+        }
+    }
+    result
+}
+
+/// npm_scripts returns the keys of `scripts` in the order of the declarations
+/// (the `preserve_order` feature of serde_json keeps the order of the keys).
+fn npm_scripts(content: &str) -> Vec<String> {
+    let value: serde_json::Value = match serde_json::from_str(content) {
+        Ok(v) => v,
+        Err(_) => return vec![],
+    };
+    match value.get("scripts").and_then(|s| s.as_object()) {
+        Some(scripts) => scripts.keys().cloned().collect(),
+        None => vec![],
+    }
+}
+
+fn dedup(tasks: Vec<String>) -> Vec<String> {
+    let mut result = Vec::<String>::new();
+    for task in tasks {
+        if !result.contains(&task) {
+            result.push(task);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_targets() {
+        let content = r#"CC := gcc
+VERSION = 1.0
+.PHONY: all clean
+all: build
+build test: main.o
+	$(CC) -o main main.o
+%.o: %.c
+	$(CC) -c $<
+clean:
+	rm -f *.o
+"#;
+        assert_eq!(
+            vec!["all", "build", "test", "clean"],
+            list(Path::new("Makefile"), content)
+        );
+    }
+
+    #[test]
+    fn test_just_recipes() {
+        let content = std::fs::read_to_string("../Justfile").unwrap();
+        let recipes = list(Path::new("Justfile"), &content);
+        assert!(recipes.contains(&"default".to_string()));
+        assert!(recipes.contains(&"build".to_string()));
+        assert!(recipes.contains(&"docker_buildx".to_string()));
+        assert!(!recipes.contains(&"VERSION".to_string()));
+    }
+
+    #[test]
+    fn test_taskfile_tasks() {
+        let content = r#"version: '3'
+
+vars:
+  GREETING: hello
+
+tasks:
+  default:
+    cmds:
+      - echo "{{.GREETING}}"
+  "docs:build":
+    desc: build the docs
+  lint: golangci-lint run
+"#;
+        assert_eq!(
+            vec!["default", "docs:build", "lint"],
+            list(Path::new("Taskfile.yml"), content)
+        );
+    }
+
+    #[test]
+    fn test_npm_scripts() {
+        let content = r#"{"name": "x", "scripts": {"test": "jest", "build": "tsc"}}"#;
+        assert_eq!(
+            vec!["test", "build"],
+            list(Path::new("package.json"), content)
+        );
+        assert!(list(Path::new("package.json"), "{}").is_empty());
+        assert!(list(Path::new("Cargo.toml"), "[package]").is_empty());
+    }
+}
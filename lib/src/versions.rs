@@ -0,0 +1,174 @@
+/*!
+ * This module detects the pinned versions of the build tools and the presence of the wrapper scripts.
+ * The version is read from the build file itself or the files placed beside it, for example,
+ * `gradle/wrapper/gradle-wrapper.properties` for Gradle, and `rust-toolchain.toml` for Cargo.
+ */
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::Source;
+
+/// the names of the files read beside the build files for the versions and the wrappers.
+const PROBED_FILES: [&str; 6] = [
+    "gradle-wrapper.properties",
+    "gradlew",
+    "maven-wrapper.properties",
+    "mvnw",
+    "rust-toolchain",
+    "rust-toolchain.toml",
+];
+
+/// is_probed returns true if the file of the given name may be read by [detect] beside the build files.
+pub(crate) fn is_probed(name: &OsStr) -> bool {
+    PROBED_FILES.iter().any(|f| OsStr::new(f) == name)
+}
+
+/// detect returns the pinned version of the build tool and whether the wrapper script exists,
+/// for the build file of the given path.
+pub(crate) fn detect(path: &Path, source: &Source) -> (Option<String>, bool) {
+    let dir = match path.parent() {
+        Some(p) => p.to_path_buf(),
+        None => PathBuf::new(),
+    };
+    let name = match path.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => return (None, false),
+    };
+    match name.as_str() {
+        "build.gradle" | "build.gradle.kts" => (
+            read_property(
+                source,
+                &dir.join("gradle/wrapper/gradle-wrapper.properties"),
+                r"gradle-([^/]+?)-(?:bin|all)\.zip",
+            ),
+            source.exists(&dir.join("gradlew")),
+        ),
+        "pom.xml" => (
+            read_property(
+                source,
+                &dir.join(".mvn/wrapper/maven-wrapper.properties"),
+                r"apache-maven-([^/]+?)-bin\.zip",
+            ),
+            source.exists(&dir.join("mvnw")),
+        ),
+        "Cargo.toml" => (rust_toolchain(source, &dir), false),
+        "CMakeLists.txt" => (
+            find_first(
+                source.read(path),
+                r"(?i)cmake_minimum_required\s*\(\s*VERSION\s+([0-9][0-9A-Za-z.]*)",
+            ),
+            false,
+        ),
+        "package.json" => (package_manager(source.read(path)), false),
+        _ => (None, false),
+    }
+}
+
+fn read_property(source: &Source, path: &Path, pattern: &str) -> Option<String> {
+    let content = source.read(path)?;
+    let url = content
+        .lines()
+        .map(|l| l.trim())
+        .find_map(|l| l.strip_prefix("distributionUrl"))?;
+    find_first(Some(url.to_string()), pattern)
+}
+
+fn rust_toolchain(source: &Source, dir: &Path) -> Option<String> {
+    if let Some(content) = source.read(&dir.join("rust-toolchain.toml")) {
+        return find_first(Some(content), r#"(?m)^\s*channel\s*=\s*"([^"]+)""#);
+    }
+    let content = source.read(&dir.join("rust-toolchain"))?;
+    if content.contains('[') {
+        find_first(Some(content), r#"(?m)^\s*channel\s*=\s*"([^"]+)""#)
+    } else {
+        content
+            .lines()
+            .map(|l| l.trim())
+            .find(|l| !l.is_empty())
+            .map(|l| l.to_string())
+    }
+}
+
+fn package_manager(content: Option<String>) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(&content?).ok()?;
+    value
+        .get("packageManager")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+fn find_first(content: Option<String>, pattern: &str) -> Option<String> {
+    let content = content?;
+    match Regex::new(pattern) {
+        Ok(re) => re
+            .captures(&content)
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str().to_string()),
+        Err(e) => {
+            log::warn!("{}: {}", pattern, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gradle_wrapper() {
+        let source = Source::Directory(Path::new("../testdata/fibonacci"));
+        let (version, wrapper) = detect(Path::new("build.gradle"), &source);
+        assert_eq!(Some("7.4.2".to_string()), version);
+        assert!(wrapper);
+    }
+
+    #[test]
+    fn test_gradle_wrapper_in_archive() {
+        let listing = crate::extractors::list_nested_contents(
+            PathBuf::from("../testdata/fibonacci.zip"),
+            0,
+            &crate::ArchiveLimits::default(),
+            &|name| Path::new(name).file_name().is_some_and(is_probed),
+        )
+        .unwrap();
+        let source = Source::Archive(&listing.contents);
+        let (version, wrapper) = detect(Path::new("fibonacci/build.gradle"), &source);
+        assert_eq!(Some("7.4.2".to_string()), version);
+        assert!(wrapper);
+    }
+
+    #[test]
+    fn test_no_version() {
+        let source = Source::Directory(Path::new("../testdata/hello"));
+        assert_eq!((None, false), detect(Path::new("Cargo.toml"), &source));
+        assert_eq!((None, false), detect(Path::new("Makefile"), &source));
+    }
+
+    #[test]
+    fn test_find_first() {
+        let pattern = r"(?i)cmake_minimum_required\s*\(\s*VERSION\s+([0-9][0-9A-Za-z.]*)";
+        assert_eq!(
+            Some("3.10".to_string()),
+            find_first(
+                Some("cmake_minimum_required(VERSION 3.10)\nproject(x)".to_string()),
+                pattern
+            )
+        );
+        assert_eq!(None, find_first(Some("project(x)".to_string()), pattern));
+    }
+
+    #[test]
+    fn test_package_manager() {
+        assert_eq!(
+            Some("pnpm@9.1.0".to_string()),
+            package_manager(Some(
+                r#"{"name":"x","packageManager":"pnpm@9.1.0"}"#.to_string()
+            ))
+        );
+        assert_eq!(None, package_manager(Some(r#"{"name":"x"}"#.to_string())));
+        assert_eq!(None, package_manager(Some("not json".to_string())));
+    }
+}
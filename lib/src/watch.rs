@@ -0,0 +1,389 @@
+/*!
+ * This module keeps the detected build tools of a directory up to date with the file system notifications
+ * (inotify, FSEvents, kqueue, or ReadDirectoryChangesW, through [notify](https://docs.rs/notify)).
+ * This module is available with the `watch` feature.
+ *
+ * Only the directories visited by the directory traversal are watched, that is,
+ * the directories ignored by the ignore types (e.g., `target/` in `.gitignore`) or matched to the exclude patterns are not watched.
+ * After the changes of the files settle, only the changed files and directories are detected again
+ * with the same ignore types and exclude patterns, and the differences from the previous result are reported as [Event]s.
+ *
+ * ```no_run
+ * let meister = btmeister::Meister::default();
+ * meister.watch(std::path::PathBuf::from("path/to/project"), |event| {
+ *     println!("{:?}", event);
+ *     true // returns false for stopping the watch.
+ * }).unwrap();
+ * ```
+ */
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::{BuildTool, Meister, MeisterError, Result};
+
+/// the period for waiting the following notifications before the detection.
+const SETTLE: Duration = Duration::from_millis(200);
+
+/// the ignore files whose modifications change the ignored entries of their directories.
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+/// Event represents a change of the detected build tools.
+#[derive(Clone, Debug)]
+pub enum Event {
+    /// the build file was detected.
+    Added(BuildTool),
+    /// the build file detected before was no longer detected.
+    Removed(BuildTool),
+}
+
+impl Event {
+    /// build_tool returns the build tool of this event.
+    pub fn build_tool(&self) -> &BuildTool {
+        match self {
+            Event::Added(bt) | Event::Removed(bt) => bt,
+        }
+    }
+}
+
+/// Watched holds the watched directories and the detected build tools of the watch.
+#[derive(Default)]
+struct Watched {
+    /// the watched directories.
+    dirs: BTreeSet<PathBuf>,
+    /// the detected build tools with the paths of the files (or the archive files) they were detected in.
+    tools: Vec<(PathBuf, BuildTool)>,
+}
+
+impl Watched {
+    fn build_tools(&self) -> Vec<BuildTool> {
+        self.tools.iter().map(|(_, bt)| bt.clone()).collect()
+    }
+}
+
+impl Meister {
+    /// watch detects the build tools in the given directory, and calls the given callback with [Event::Added]
+    /// for each detected file at first.
+    /// Then, this function watches the directory, and calls the callback with the added and removed build files
+    /// whenever the files in the directory are created, renamed, modified, or deleted.
+    /// The changes of the ignored and excluded files are ignored.
+    ///
+    /// This function blocks the current thread until the callback returns `false`.
+    pub fn watch<F>(&self, base: PathBuf, mut callback: F) -> Result<()>
+    where
+        F: FnMut(Event) -> bool,
+    {
+        if !base.exists() {
+            return Err(MeisterError::ProjectNotFound(base));
+        } else if !base.is_dir() {
+            return Err(MeisterError::NotProject(format!(
+                "{} (not a directory)",
+                base.display()
+            )));
+        }
+        // the notifications are reported with the absolute paths.
+        let root = base.canonicalize().map_err(MeisterError::IO)?;
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx).map_err(watch_error)?;
+        let mut watched = Watched::default();
+        let mut targets = BTreeSet::from([root.clone()]);
+        loop {
+            let before = watched.build_tools();
+            for target in reduce(targets) {
+                self.rescan(&base, &root, &target, &mut watcher, &mut watched);
+            }
+            for event in changes(&before, &watched.build_tools()) {
+                if !callback(event) {
+                    return Ok(());
+                }
+            }
+            targets = BTreeSet::new();
+            let event = match rx.recv() {
+                Ok(event) => event,
+                Err(_) => return Ok(()),
+            };
+            self.collect(&root, event, &watched, &mut targets);
+            while let Ok(event) = rx.recv_timeout(SETTLE) {
+                self.collect(&root, event, &watched, &mut targets);
+            }
+        }
+    }
+
+    /// collect adds the files and directories to detect again by the given notification into `targets`.
+    fn collect(
+        &self,
+        root: &Path,
+        event: notify::Result<notify::Event>,
+        watched: &Watched,
+        targets: &mut BTreeSet<PathBuf>,
+    ) {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                // some notifications may be lost, therefore, the whole directory is detected again.
+                log::warn!("{}: {}", root.display(), e);
+                targets.insert(root.to_path_buf());
+                return;
+            }
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in event.paths {
+            let target = match path.file_name() {
+                Some(name) if IGNORE_FILES.iter().any(|f| name == *f) => match path.parent() {
+                    Some(parent) => parent.to_path_buf(),
+                    None => continue,
+                },
+                _ => path,
+            };
+            if !target.starts_with(root) {
+                continue;
+            }
+            let removed = !target.exists();
+            if removed && watched.tools.iter().any(|(p, _)| p.starts_with(&target))
+                || !removed && self.is_walked(root, &target, watched)
+            {
+                targets.insert(target);
+            }
+        }
+    }
+
+    /// is_walked returns true if the given file or directory is visited by the directory traversal,
+    /// i.e., it is neither ignored by the ignore types nor matched to the exclude patterns.
+    fn is_walked(&self, root: &Path, target: &Path, watched: &Watched) -> bool {
+        if target == root {
+            return true;
+        }
+        let parent = match target.parent() {
+            Some(parent) if watched.dirs.contains(parent) => parent,
+            _ => return false,
+        };
+        self.walker_at(root.to_path_buf(), parent)
+            .max_depth(Some(1))
+            .build()
+            .filter_map(|entry| entry.ok())
+            .any(|entry| entry.path() == target)
+    }
+
+    /// rescan detects the build tools in the given file or subtree again, and updates the watched directories.
+    /// The warnings while walking the subtree are logged, and the detection goes on after them.
+    fn rescan<W: Watcher>(
+        &self,
+        base: &Path,
+        root: &Path,
+        target: &Path,
+        watcher: &mut W,
+        watched: &mut Watched,
+    ) {
+        watched.tools.retain(|(p, _)| !p.starts_with(target));
+        let stale = watched
+            .dirs
+            .iter()
+            .filter(|d| d.starts_with(target))
+            .cloned()
+            .collect::<Vec<PathBuf>>();
+        for dir in stale {
+            let _ = watcher.unwatch(&dir);
+            watched.dirs.remove(&dir);
+        }
+        if !target.exists() {
+            return;
+        }
+        log::debug!("{}: detecting", target.display());
+        for entry in self.walker_at(root.to_path_buf(), target).build() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    log::warn!("{}: walking: {}", target.display(), e);
+                    continue;
+                }
+            };
+            let path = entry.path().to_path_buf();
+            if entry.file_type().is_some_and(|t| t.is_dir()) {
+                match watcher.watch(&path, RecursiveMode::NonRecursive) {
+                    Ok(_) => {
+                        watched.dirs.insert(path.clone());
+                    }
+                    Err(e) => log::warn!("{}: watch: {}", path.display(), e),
+                }
+            }
+            if let Some(bt) = self.find_entry(root, &path) {
+                watched.tools.push((path.clone(), bt));
+            }
+            if self.opens_archive(&entry) {
+                // the archive paths are reported under the given base, like [Meister::find].
+                let rel = path.strip_prefix(root).unwrap_or(&path);
+                let tools = self.find_in_archive(&base.join(rel), None);
+                watched
+                    .tools
+                    .extend(tools.into_iter().map(|bt| (path.clone(), bt)));
+            }
+        }
+    }
+}
+
+/// reduce removes the targets under the other targets, since the subtrees of the targets are detected again.
+fn reduce(targets: BTreeSet<PathBuf>) -> Vec<PathBuf> {
+    let mut result: Vec<PathBuf> = vec![];
+    for target in targets {
+        if !result.iter().any(|r| target.starts_with(r)) {
+            result.push(target);
+        }
+    }
+    result
+}
+
+/// changes returns the removed build files, and then the added build files from `before` to `after`.
+fn changes(before: &[BuildTool], after: &[BuildTool]) -> Vec<Event> {
+    let contains = |tools: &[BuildTool], bt: &BuildTool| {
+        tools
+            .iter()
+            .any(|t| t.path == bt.path && t.def.name == bt.def.name)
+    };
+    let removed = before
+        .iter()
+        .filter(|bt| !contains(after, bt))
+        .map(|bt| Event::Removed(bt.clone()));
+    let added = after
+        .iter()
+        .filter(|bt| !contains(before, bt))
+        .map(|bt| Event::Added(bt.clone()));
+    removed.chain(added).collect()
+}
+
+fn watch_error(e: notify::Error) -> MeisterError {
+    MeisterError::Fatal(format!("watch: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildTools;
+
+    #[test]
+    fn test_changes() {
+        let tool = |path: &str, name: &str| {
+            BuildTool::new(
+                PathBuf::from(path),
+                crate::defs::BuildToolDef::new(name.to_string(), vec![], "".to_string()),
+            )
+        };
+        let before = BuildTools::new(
+            PathBuf::from("base"),
+            vec![
+                tool("base/Makefile", "Make"),
+                tool("base/pom.xml", "Apache Maven"),
+            ],
+        );
+        let after = BuildTools::new(
+            PathBuf::from("base"),
+            vec![
+                tool("base/Makefile", "Make"),
+                tool("base/build.gradle", "Gradle"),
+            ],
+        );
+        let events = changes(&before.tools, &after.tools)
+            .into_iter()
+            .map(|e| match e {
+                Event::Added(bt) => format!("+{}", bt.def.name),
+                Event::Removed(bt) => format!("-{}", bt.def.name),
+            })
+            .collect::<Vec<String>>();
+        assert_eq!(vec!["-Apache Maven", "+Gradle"], events);
+    }
+
+    #[test]
+    fn test_watch() {
+        let dir = std::env::temp_dir().join(format!("btmeister-watch-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("Makefile"), "all:\n").unwrap();
+        let (tx, rx) = mpsc::channel();
+        let base = dir.clone();
+        let handle = std::thread::spawn(move || {
+            Meister::default().watch(base, |event| {
+                let stop = matches!(&event, Event::Removed(_));
+                let _ = tx.send(event);
+                !stop
+            })
+        });
+        let first = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(matches!(first, Event::Added(bt) if bt.def.name == "Make"));
+
+        std::fs::write(dir.join("build.gradle"), "").unwrap();
+        let added = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(matches!(added, Event::Added(bt) if bt.def.name == "Gradle"));
+
+        std::fs::remove_file(dir.join("Makefile")).unwrap();
+        let removed = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(matches!(removed, Event::Removed(bt) if bt.def.name == "Make"));
+        assert!(handle.join().unwrap().is_ok());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_rescan() {
+        let dir = std::env::temp_dir().join(format!("btmeister-rescan-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("target")).unwrap();
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join(".ignore"), "target/\n").unwrap();
+        std::fs::write(dir.join("Makefile"), "all:\n").unwrap();
+        std::fs::write(dir.join("target/pom.xml"), "").unwrap();
+        let root = dir.canonicalize().unwrap();
+        let meister = Meister::default();
+        let (tx, _rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx).unwrap();
+        let mut watched = Watched::default();
+        meister.rescan(&dir, &root, &root, &mut watcher, &mut watched);
+        assert_eq!(
+            BTreeSet::from([root.clone(), root.join("sub")]),
+            watched.dirs
+        );
+        let names = |watched: &Watched| {
+            watched
+                .tools
+                .iter()
+                .map(|(_, bt)| bt.def.name.clone())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(vec!["Make"], names(&watched));
+
+        // the ignored entries are not detected again.
+        assert!(!meister.is_walked(&root, &root.join("target/pom.xml"), &watched));
+        assert!(!meister.is_walked(&root, &root.join("target"), &watched));
+        std::fs::write(dir.join("sub/build.gradle"), "").unwrap();
+        assert!(meister.is_walked(&root, &root.join("sub/build.gradle"), &watched));
+
+        // only the given subtree is detected again.
+        meister.rescan(&dir, &root, &root.join("sub"), &mut watcher, &mut watched);
+        assert_eq!(vec!["Make", "Gradle"], names(&watched));
+        std::fs::remove_dir_all(dir.join("sub")).unwrap();
+        meister.rescan(&dir, &root, &root.join("sub"), &mut watcher, &mut watched);
+        assert_eq!(vec!["Make"], names(&watched));
+        assert_eq!(BTreeSet::from([root.clone()]), watched.dirs);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_reduce() {
+        let targets = BTreeSet::from([
+            PathBuf::from("a/b"),
+            PathBuf::from("a"),
+            PathBuf::from("ab"),
+            PathBuf::from("c/d"),
+        ]);
+        assert_eq!(
+            vec![
+                PathBuf::from("a"),
+                PathBuf::from("ab"),
+                PathBuf::from("c/d")
+            ],
+            reduce(targets)
+        );
+    }
+}